@@ -0,0 +1,178 @@
+//! Asynchronous delays
+
+use crate::blocking::delay::{CountDowns, Microseconds};
+use crate::nb::timer::CountDown;
+
+/// Nanosecond delay (asynchronous variant)
+///
+/// Only `delay_ns` must be implemented. The provided `delay_us` and `delay_ms` methods split
+/// delays which would overflow a `u32` amount of nanoseconds into several `delay_ns` calls.
+pub trait DelayNs {
+    /// Enumeration of `DelayNs` errors
+    type Error;
+
+    /// Pauses execution for at least `ns` nanoseconds
+    ///
+    /// The pause can be longer if the implementation requires it due to precision or timing
+    /// issues.
+    async fn delay_ns(&mut self, ns: u32) -> Result<(), Self::Error>;
+
+    /// Pauses execution for at least `us` microseconds
+    async fn delay_us(&mut self, mut us: u32) -> Result<(), Self::Error> {
+        const MAX_MICROS: u32 = u32::MAX / 1_000;
+
+        while us > MAX_MICROS {
+            self.delay_ns(MAX_MICROS * 1_000).await?;
+            us -= MAX_MICROS;
+        }
+
+        self.delay_ns(us * 1_000).await
+    }
+
+    /// Pauses execution for at least `ms` milliseconds
+    async fn delay_ms(&mut self, mut ms: u32) -> Result<(), Self::Error> {
+        const MAX_MILLIS: u32 = u32::MAX / 1_000_000;
+
+        while ms > MAX_MILLIS {
+            self.delay_ns(MAX_MILLIS * 1_000_000).await?;
+            ms -= MAX_MILLIS;
+        }
+
+        self.delay_ns(ms * 1_000_000).await
+    }
+}
+
+/// Asynchronous delays implemented on top of a
+/// [`CountDown`](../../nb/timer/trait.CountDown.html) timer
+///
+/// This is the asynchronous counterpart of the blocking
+/// [`CountDownDelay`](../../blocking/delay/struct.CountDownDelay.html): while the timer is
+/// counting down, the future yields to the executor, letting other tasks run.
+///
+/// *NOTE* the future wakes itself before yielding, so the executor keeps polling the timer and
+/// cannot sleep until the delay is over, see the [module documentation](../index.html).
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::asynch::delay::{CountDownDelay, DelayNs};
+/// use embedded_hal::blocking::delay::Microseconds;
+/// use embedded_hal::nb::timer::{CountDown, ErrorType};
+/// use core::convert::Infallible;
+///
+/// # /// Runs `future` to completion, returning its output and the number of times it yielded
+/// # fn block_on<F: core::future::Future>(future: F) -> (F::Output, usize) {
+/// #     use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+/// #     fn raw() -> RawWaker { RawWaker::new(core::ptr::null(), &VTABLE) }
+/// #     static VTABLE: RawWakerVTable = RawWakerVTable::new(|_| raw(), |_| {}, |_| {}, |_| {});
+/// #     let waker = unsafe { Waker::from_raw(raw()) };
+/// #     let mut cx = Context::from_waker(&waker);
+/// #     let mut future = core::pin::pin!(future);
+/// #     let mut yields = 0;
+/// #     loop {
+/// #         match future.as_mut().poll(&mut cx) {
+/// #             Poll::Ready(output) => return (output, yields),
+/// #             Poll::Pending => yields += 1,
+/// #         }
+/// #     }
+/// # }
+/// /// A timer with a resolution of 100 us, expiring after being polled once per tick
+/// struct Timer {
+///     remaining_ticks: u32,
+///     started: Vec<u32>,
+/// }
+///
+/// impl ErrorType for Timer {
+///     type Error = Infallible;
+/// }
+///
+/// impl CountDown for Timer {
+///     type Time = Microseconds;
+///
+///     fn start<T: Into<Microseconds>>(&mut self, count: T) -> Result<(), Infallible> {
+///         let us = count.into().0;
+///         self.started.push(us);
+///         self.remaining_ticks = (us + 99) / 100;
+///         Ok(())
+///     }
+///
+///     fn wait(&mut self) -> nb::Result<(), Infallible> {
+///         match self.remaining_ticks {
+///             0 => Ok(()),
+///             _ => {
+///                 self.remaining_ticks -= 1;
+///                 Err(nb::Error::WouldBlock)
+///             }
+///         }
+///     }
+/// }
+///
+/// // the timer can count down for at most 1 ms
+/// let timer = Timer { remaining_ticks: 0, started: Vec::new() };
+/// let mut delay = CountDownDelay::new(timer, 1_000);
+///
+/// assert_eq!(block_on(delay.delay_us(300)), (Ok(()), 3));
+/// assert_eq!(block_on(delay.delay_ms(2)), (Ok(()), 20));
+/// assert_eq!(delay.release().started, [300, 1_000, 1_000]);
+/// ```
+pub struct CountDownDelay<T> {
+    timer: T,
+    max_us: u32,
+}
+
+impl<T> CountDownDelay<T> {
+    /// Creates a new `CountDownDelay` from a timer
+    ///
+    /// `max_us` is the longest count down the timer supports, in microseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_us` is 0.
+    pub fn new(timer: T, max_us: u32) -> Self {
+        assert!(
+            max_us > 0,
+            "the timer must support count downs of at least 1 us"
+        );
+        Self { timer, max_us }
+    }
+
+    /// Destroys the delay and returns the timer
+    pub fn release(self) -> T {
+        self.timer
+    }
+}
+
+impl<T> CountDownDelay<T>
+where
+    T: CountDown,
+    T::Time: From<Microseconds>,
+{
+    async fn delay(&mut self, count_downs: CountDowns) -> Result<(), T::Error> {
+        for count in count_downs {
+            self.timer.start(count)?;
+            super::poll_nb(|| self.timer.wait()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> DelayNs for CountDownDelay<T>
+where
+    T: CountDown,
+    T::Time: From<Microseconds>,
+{
+    type Error = T::Error;
+
+    async fn delay_ns(&mut self, ns: u32) -> Result<(), Self::Error> {
+        self.delay(CountDowns::ns(ns, self.max_us)).await
+    }
+
+    async fn delay_us(&mut self, us: u32) -> Result<(), Self::Error> {
+        self.delay(CountDowns::us(us, self.max_us)).await
+    }
+
+    async fn delay_ms(&mut self, ms: u32) -> Result<(), Self::Error> {
+        self.delay(CountDowns::ms(ms, self.max_us)).await
+    }
+}
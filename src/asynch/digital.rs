@@ -0,0 +1,55 @@
+//! Asynchronous digital I/O
+
+pub use crate::blocking::digital::{Error, ErrorKind, ErrorType};
+use crate::blocking::digital::{InputPin, PollingWait};
+
+/// Asynchronously wait for the state of an input pin
+///
+/// The methods complete as soon as the awaited level or edge is detected on the pin. Only
+/// edges happening after the method is called are detected.
+pub trait Wait: ErrorType {
+    /// Waits until the pin is high
+    ///
+    /// Completes immediately if the pin is already high.
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error>;
+
+    /// Waits until the pin is low
+    ///
+    /// Completes immediately if the pin is already low.
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error>;
+
+    /// Waits for the pin to undergo a transition from low to high
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error>;
+
+    /// Waits for the pin to undergo a transition from high to low
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error>;
+
+    /// Waits for the pin to undergo any transition, i.e low to high OR high to low
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Samples the pin, yielding to the executor between samples
+impl<P: InputPin> Wait for PollingWait<P> {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        super::poll_nb(|| self.poll_level(true)).await
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        super::poll_nb(|| self.poll_level(false)).await
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.reset();
+        super::poll_nb(|| self.poll_edge(true, false)).await
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.reset();
+        super::poll_nb(|| self.poll_edge(false, true)).await
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.reset();
+        super::poll_nb(|| self.poll_edge(true, true)).await
+    }
+}
@@ -0,0 +1,101 @@
+//! Asynchronous I2C API
+//!
+//! See the [`blocking::i2c`](../../blocking/i2c/index.html) module for the details of the
+//! address modes and of the I2C events of each operation.
+
+pub use crate::blocking::i2c::{
+    AddressMode, Error, ErrorKind, ErrorType, NoAcknowledgeSource, Operation, SevenBitAddress,
+    TenBitAddress,
+};
+
+/// Asynchronous read
+pub trait Read<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Reads enough bytes from slave with `address` to fill `buffer`
+    ///
+    /// # I2C Events (contract)
+    ///
+    /// Same as the blocking `Read` trait
+    async fn read(&mut self, address: A, buffer: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous write
+pub trait Write<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Writes bytes to slave with address `address`
+    ///
+    /// # I2C Events (contract)
+    ///
+    /// Same as the blocking `Write` trait
+    async fn write(&mut self, address: A, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous write (iterator version)
+pub trait WriteIter<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Writes bytes to slave with address `address`
+    ///
+    /// # I2C Events (contract)
+    ///
+    /// Same as the blocking `Write` trait
+    async fn write_iter<B>(&mut self, address: A, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>;
+}
+
+/// Asynchronous write + read
+pub trait WriteRead<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Writes bytes to slave with address `address` and then reads enough bytes to fill `buffer` *in a
+    /// single transaction*
+    ///
+    /// # I2C Events (contract)
+    ///
+    /// Same as the blocking `WriteRead` trait
+    async fn write_read(
+        &mut self,
+        address: A,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous write (iterator version) + read
+pub trait WriteIterRead<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Writes bytes to slave with address `address` and then reads enough bytes to fill `buffer` *in a
+    /// single transaction*
+    ///
+    /// # I2C Events (contract)
+    ///
+    /// Same as the blocking `WriteRead` trait
+    async fn write_iter_read<B>(
+        &mut self,
+        address: A,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>;
+}
+
+/// Asynchronous transactional I2C interface
+///
+/// This allows combining operations within an I2C transaction.
+pub trait Transactional<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Execute the provided operations on the I2C bus
+    ///
+    /// Transaction contract: same as the blocking `Transactional` trait
+    async fn exec<'a>(
+        &mut self,
+        address: A,
+        operations: &mut [Operation<'a>],
+    ) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous transactional I2C interface (iterator version)
+///
+/// This allows combining operations within an I2C transaction.
+pub trait TransactionalIter<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Execute the provided operations on the I2C bus (iterator version)
+    ///
+    /// Transaction contract: same as the blocking `Transactional` trait
+    async fn exec_iter<'a, O>(&mut self, address: A, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Operation<'a>>;
+}
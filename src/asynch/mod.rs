@@ -0,0 +1,63 @@
+//! Asynchronous API
+//!
+//! These traits mirror the blocking traits, using `async fn`s instead of blocking methods. They
+//! share the `ErrorType` and `Error` traits of the corresponding blocking traits, so that a
+//! peripheral implementing both flavours has a single error type.
+//!
+//! Adapters are provided to implement some of these traits on top of the non-blocking traits of
+//! the [`nb`](../nb/index.html) module. They poll the non-blocking implementation and yield to
+//! the executor whenever it would block, letting other tasks run.
+//!
+//! *NOTE* the `nb` traits cannot signal when an operation can make progress, so the adapters
+//! wake their task before yielding: the executor polls them again right away, and never sleeps
+//! (e.g. with `WFE`) while they wait. This is still busy polling, one level up. HAL
+//! implementations of the asynchronous traits woken by interrupts should be preferred where
+//! power consumption matters.
+//!
+//! *This module is available if embedded-hal is built with the `"async"` feature, which requires
+//! Rust 1.75 or newer.*
+
+#![allow(async_fn_in_trait)]
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+pub mod delay;
+pub mod digital;
+pub mod i2c;
+pub mod serial;
+pub mod spi;
+
+/// Future yielding to the executor once before completing
+struct YieldNow {
+    yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// Polls the non-blocking operation `f` until it completes, yielding whenever it would block
+async fn poll_nb<T, E, F>(mut f: F) -> Result<T, E>
+where
+    F: FnMut() -> nb::Result<T, E>,
+{
+    loop {
+        match f() {
+            Ok(value) => return Ok(value),
+            Err(nb::Error::Other(e)) => return Err(e),
+            Err(nb::Error::WouldBlock) => YieldNow { yielded: false }.await,
+        }
+    }
+}
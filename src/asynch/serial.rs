@@ -0,0 +1,221 @@
+//! Asynchronous serial API
+
+pub use crate::blocking::serial::{Error, ErrorKind, ErrorType};
+use crate::nb::serial as nb_serial;
+
+/// Read half of a serial interface (asynchronous variant)
+pub trait Read<Word>: ErrorType {
+    /// Reads words until `buffer` is full
+    async fn read_exact(&mut self, buffer: &mut [Word]) -> Result<(), Self::Error>;
+
+    /// Reads the words already received, waiting until there is at least one
+    ///
+    /// Waits until at least one word has been received, unless `buffer` is empty. Then reads
+    /// the words which have already been received, e.g. into a FIFO, until `buffer` is full,
+    /// without waiting for any further word. Returns the number of words read into `buffer`.
+    ///
+    /// This does not detect the end of a message: the next word of a message may still be in
+    /// transit when this method returns.
+    ///
+    /// If an error occurs after some words have been read, the number of words read is returned
+    /// and the error is reported by the next call.
+    async fn read(&mut self, buffer: &mut [Word]) -> Result<usize, Self::Error>;
+}
+
+/// Write half of a serial interface (asynchronous variant)
+pub trait Write<Word>: ErrorType {
+    /// Writes a slice
+    ///
+    /// An implementation can choose to buffer the write, returning `Ok(())`
+    /// after the complete slice has been written to a buffer, but before all
+    /// words have been sent via the serial interface. To make sure that
+    /// everything has been sent, call [`flush`] after this function returns.
+    ///
+    /// [`flush`]: #tymethod.flush
+    async fn write(&mut self, buffer: &[Word]) -> Result<(), Self::Error>;
+
+    /// Waits until the serial interface has sent all buffered words
+    async fn flush(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous serial traits implemented on top of the [`nb::serial`] traits
+///
+/// This type implements `Read` for any `nb::serial::Read` implementation and `Write` for any
+/// `nb::serial::Write` implementation. Whenever the `nb::serial` implementation would block,
+/// the future yields to the executor, letting other tasks run. After the first word, `read` only
+/// reads the words which the `nb::serial::Read` implementation returns without `WouldBlock`,
+/// i.e. the words already received. An error returned after the first word is kept and reported
+/// by the next call to `read` or `read_exact`.
+///
+/// *NOTE* the futures wake themselves before yielding, so the executor keeps polling the serial
+/// interface and cannot sleep while waiting for a word, see the
+/// [module documentation](../index.html).
+///
+/// [`nb::serial`]: ../../nb/serial/index.html
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::asynch::serial::{ErrorKind, ErrorType, NbSerial, Read, Write};
+/// use embedded_hal::nb::serial;
+/// use std::collections::VecDeque;
+///
+/// # /// Runs `future` to completion, returning its output and the number of times it yielded
+/// # fn block_on<F: core::future::Future>(future: F) -> (F::Output, usize) {
+/// #     use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+/// #     fn raw() -> RawWaker { RawWaker::new(core::ptr::null(), &VTABLE) }
+/// #     static VTABLE: RawWakerVTable = RawWakerVTable::new(|_| raw(), |_| {}, |_| {}, |_| {});
+/// #     let waker = unsafe { Waker::from_raw(raw()) };
+/// #     let mut cx = Context::from_waker(&waker);
+/// #     let mut future = core::pin::pin!(future);
+/// #     let mut yields = 0;
+/// #     loop {
+/// #         match future.as_mut().poll(&mut cx) {
+/// #             Poll::Ready(output) => return (output, yields),
+/// #             Poll::Pending => yields += 1,
+/// #         }
+/// #     }
+/// # }
+/// /// A serial interface receiving words from a queue, where `None` stands for no word available
+/// struct Serial {
+///     rx: VecDeque<Option<u8>>,
+///     tx: Vec<u8>,
+/// }
+///
+/// impl ErrorType for Serial {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl serial::Read<u8> for Serial {
+///     fn read(&mut self) -> nb::Result<u8, ErrorKind> {
+///         match self.rx.pop_front() {
+///             Some(Some(word)) => Ok(word),
+///             Some(None) => Err(nb::Error::WouldBlock),
+///             None => Err(nb::Error::Other(ErrorKind::Overrun)),
+///         }
+///     }
+/// }
+///
+/// impl serial::Write<u8> for Serial {
+///     fn write(&mut self, word: u8) -> nb::Result<(), ErrorKind> {
+///         self.tx.push(word);
+///         Ok(())
+///     }
+///
+///     fn flush(&mut self) -> nb::Result<(), ErrorKind> {
+///         Ok(())
+///     }
+/// }
+///
+/// let rx = vec![None, None, Some(1), Some(2), None, Some(3), None, Some(4), Some(5)];
+/// let mut serial = NbSerial::new(Serial { rx: rx.into(), tx: Vec::new() });
+///
+/// // `read` waits for the first word, yielding while no word is available, and stops when no
+/// // more word is available
+/// let mut buffer = [0; 4];
+/// assert_eq!(block_on(serial.read(&mut buffer)), (Ok(2), 2));
+/// assert_eq!(buffer[..2], [1, 2]);
+///
+/// // `read_exact` yields until the buffer is full
+/// let mut buffer = [0; 2];
+/// assert_eq!(block_on(serial.read_exact(&mut buffer)), (Ok(()), 1));
+/// assert_eq!(buffer, [3, 4]);
+///
+/// // an error following received words is reported by the next call
+/// assert_eq!(block_on(serial.read(&mut buffer)), (Ok(1), 0));
+/// assert_eq!(buffer[..1], [5]);
+/// assert_eq!(block_on(serial.read(&mut buffer)).0, Err(ErrorKind::Overrun));
+///
+/// // errors on the first word are reported right away
+/// assert_eq!(block_on(serial.read(&mut buffer)).0, Err(ErrorKind::Overrun));
+///
+/// block_on(serial.write(b"ok")).0.unwrap();
+/// assert_eq!(serial.release().tx, b"ok");
+/// ```
+pub struct NbSerial<S: ErrorType> {
+    serial: S,
+    /// Error returned by the wrapped implementation after the first word of a `read`
+    pending: Option<S::Error>,
+}
+
+impl<S: ErrorType> NbSerial<S> {
+    /// Creates a new `NbSerial` wrapping an `nb::serial` implementation
+    pub fn new(serial: S) -> Self {
+        Self {
+            serial,
+            pending: None,
+        }
+    }
+
+    /// Destroys the adapter and returns the wrapped `nb::serial` implementation
+    pub fn release(self) -> S {
+        self.serial
+    }
+}
+
+impl<S: ErrorType> ErrorType for NbSerial<S> {
+    type Error = S::Error;
+}
+
+impl<Word, S> Read<Word> for NbSerial<S>
+where
+    S: nb_serial::Read<Word>,
+{
+    async fn read_exact(&mut self, buffer: &mut [Word]) -> Result<(), Self::Error> {
+        if let Some(e) = self.pending.take() {
+            return Err(e);
+        }
+
+        for word in buffer.iter_mut() {
+            *word = super::poll_nb(|| self.serial.read()).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn read(&mut self, buffer: &mut [Word]) -> Result<usize, Self::Error> {
+        if let Some(e) = self.pending.take() {
+            return Err(e);
+        }
+
+        let (first, rest) = match buffer.split_first_mut() {
+            Some(split) => split,
+            None => return Ok(0),
+        };
+        *first = super::poll_nb(|| self.serial.read()).await?;
+
+        let mut count = 1;
+        for word in rest {
+            match self.serial.read() {
+                Ok(w) => *word = w,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => {
+                    // report the error once the words already read are returned
+                    self.pending = Some(e);
+                    break;
+                }
+            }
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+impl<Word, S> Write<Word> for NbSerial<S>
+where
+    Word: Copy,
+    S: nb_serial::Write<Word>,
+{
+    async fn write(&mut self, buffer: &[Word]) -> Result<(), Self::Error> {
+        for word in buffer {
+            super::poll_nb(|| self.serial.write(*word)).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Self::Error> {
+        super::poll_nb(|| self.serial.flush()).await
+    }
+}
@@ -0,0 +1,41 @@
+//! Asynchronous SPI API
+//!
+//! Like the blocking `Transfer`, `Write`, `WriteIter` and `Transactional` traits, these traits
+//! leave the management of chip select lines to their users.
+
+pub use crate::blocking::spi::{Error, ErrorKind, ErrorType, Operation};
+
+/// Asynchronous transfer
+pub trait Transfer<W>: ErrorType {
+    /// Writes `words` to the slave. Returns the `words` received from the slave
+    async fn transfer<'w>(&mut self, words: &'w mut [W]) -> Result<&'w [W], Self::Error>;
+}
+
+/// Asynchronous write
+pub trait Write<W>: ErrorType {
+    /// Writes `words` to the slave, ignoring all the incoming words
+    async fn write(&mut self, words: &[W]) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous write (iterator version)
+pub trait WriteIter<W>: ErrorType {
+    /// Writes `words` to the slave, ignoring all the incoming words
+    async fn write_iter<WI>(&mut self, words: WI) -> Result<(), Self::Error>
+    where
+        WI: IntoIterator<Item = W>;
+}
+
+/// Asynchronous transactional trait allowing multiple actions to be executed
+/// as part of a single SPI transaction
+pub trait Transactional<W: 'static>: ErrorType {
+    /// Execute the provided transactions
+    async fn exec<'a>(&mut self, operations: &mut [Operation<'a, W>]) -> Result<(), Self::Error>;
+}
+
+/// Asynchronous SPI bus
+///
+/// See the blocking [`SpiBus`](../../blocking/spi/trait.SpiBus.html) trait.
+pub trait SpiBus<W: 'static>: Transfer<W> + Write<W> + Transactional<W> {
+    /// Waits until all operations have completed and the bus is idle
+    async fn flush(&mut self) -> Result<(), Self::Error>;
+}
@@ -0,0 +1,800 @@
+//! Bit-banged I2C master
+
+use crate::blocking::delay::DelayNs;
+use crate::blocking::digital::{self, OpenDrainPin};
+use crate::blocking::i2c::{
+    Error, ErrorKind, ErrorType, NoAcknowledgeSource, Operation, Read, SevenBitAddress,
+    TenBitAddress, Transactional, Write, WriteRead,
+};
+
+/// Default clock stretching timeout, in microseconds
+const DEFAULT_STRETCH_TIMEOUT_US: u32 = 25_000;
+
+/// Error type of [`BitBangI2c`](struct.BitBangI2c.html)
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BitBangI2cError<PIN, DELAY> {
+    /// Driving or reading a pin failed
+    Pin(PIN),
+    /// Waiting for half a clock period failed
+    Delay(DELAY),
+    /// The address or a data byte was not acknowledged
+    NoAcknowledge(NoAcknowledgeSource),
+    /// SDA was low while releasing it, i.e. another master is using the bus
+    ArbitrationLoss,
+    /// A device held SCL low for longer than the clock stretching timeout
+    ClockStretchTimeout,
+    /// SDA is still held low after 9 clock pulses of a bus recovery
+    BusStuck,
+}
+
+impl<PIN, DELAY> Error for BitBangI2cError<PIN, DELAY>
+where
+    PIN: core::fmt::Debug,
+    DELAY: core::fmt::Debug,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NoAcknowledge(source) => ErrorKind::NoAcknowledge(*source),
+            Self::ArbitrationLoss => ErrorKind::ArbitrationLoss,
+            Self::BusStuck => ErrorKind::Bus,
+            Self::Pin(_) | Self::Delay(_) | Self::ClockStretchTimeout => ErrorKind::Other,
+        }
+    }
+}
+
+/// Address of the target of a transaction
+#[derive(Clone, Copy)]
+enum Address {
+    Seven(SevenBitAddress),
+    Ten(TenBitAddress),
+}
+
+/// I2C master bit-banged on an SCL and an SDA open-drain pin
+///
+/// This type implements the `Read`, `Write`, `WriteRead` and `Transactional` I2C traits, for both
+/// 7-bit and 10-bit addresses, following the I2C events described in the contracts of these
+/// traits. It can serve as a reference for the expected bus behaviour:
+///
+/// - A transaction starts with a start condition (ST) and ends with a stop condition (SP).
+///   Operations of a different type than the previous one are preceded by a repeated start
+///   condition (SR) and the address of the target.
+/// - The last byte read before a repeated start or stop condition is not acknowledged by the
+///   master (NMAK), all the other bytes are (MAK).
+/// - Once the target acknowledged a read address, it may already drive the first bit of a byte,
+///   so the master cannot send a repeated start or stop condition before reading a byte. When
+///   the read operations following a read address read no byte, e.g. for the SMBus Quick
+///   Command, a byte is read and not acknowledged, then discarded.
+/// - When the target does not acknowledge its address or a byte written to it, a stop condition
+///   is sent and a `NoAcknowledge` error is returned.
+/// - 10-bit addresses are sent as a `11110xx0` header byte followed by the 8 low bits of the
+///   address. Reads are addressed by a repeated start condition followed by the `11110xx1`
+///   header byte, after a full 10-bit address if the transaction did not address the target
+///   yet.
+///
+/// Each bit takes a full clock period, i.e. two calls to `delay_ns` with the half period given
+/// to [`new`]. Devices may stretch the clock by holding SCL low, for at most the clock
+/// stretching timeout (25 ms by default, see
+/// [`set_stretch_timeout`](#method.set_stretch_timeout)). When another master drives SDA low
+/// while this master releases it, the transaction is aborted with an `ArbitrationLoss` error.
+///
+/// [`new`]: #method.new
+///
+/// # Examples
+///
+/// Talking to a simulated device, recording the I2C events:
+///
+/// ```
+/// use embedded_hal::bitbang::i2c::{BitBangI2c, BitBangI2cError};
+/// use embedded_hal::blocking::delay::DelayNs;
+/// use embedded_hal::blocking::digital::{ErrorType, InputPin, OpenDrainPin};
+/// use embedded_hal::blocking::i2c::{
+///     NoAcknowledgeSource, Operation, Read, TenBitAddress, Transactional, Write, WriteRead,
+/// };
+/// use embedded_hal::blocking::smbus::Smbus;
+/// use core::cell::RefCell;
+/// use core::convert::Infallible;
+///
+/// /// Address bytes acknowledged by the device: 0x50 and 0x134
+/// const ADDRESS_BYTES: [u8; 4] = [0xA0, 0xA1, 0xF2, 0xF3];
+///
+/// /// An I2C bus with a device sending incrementing bytes, starting at 0x10
+/// struct Bus {
+///     scl: bool,
+///     master_sda: bool,
+///     device_sda: bool,
+///     stretch: u32,
+///     bit: u8,
+///     byte: u8,
+///     address_phase: bool,
+///     transmitting: bool,
+///     send_next: bool,
+///     data: u8,
+///     events: Vec<String>,
+/// }
+///
+/// impl Bus {
+///     fn new() -> Self {
+///         Bus {
+///             scl: true,
+///             master_sda: true,
+///             device_sda: true,
+///             stretch: 0,
+///             bit: 0,
+///             byte: 0,
+///             address_phase: false,
+///             transmitting: false,
+///             send_next: false,
+///             data: 0x0F,
+///             events: Vec::new(),
+///         }
+///     }
+///
+///     fn sda(&self) -> bool {
+///         self.master_sda && self.device_sda
+///     }
+///
+///     fn set_sda(&mut self, level: bool) {
+///         let previous = self.sda();
+///         self.master_sda = level;
+///         if self.scl && previous && !self.sda() {
+///             let start = if self.address_phase || self.bit > 0 { "SR" } else { "ST" };
+///             self.events.push(start.into());
+///             self.address_phase = true;
+///             self.transmitting = false;
+///             self.device_sda = true;
+///             self.bit = 0;
+///         } else if self.scl && !previous && self.sda() {
+///             self.events.push("SP".into());
+///             self.address_phase = false;
+///             self.bit = 0;
+///         }
+///     }
+///
+///     fn set_scl(&mut self, level: bool) {
+///         if level == self.scl {
+///             return;
+///         }
+///         self.scl = level;
+///
+///         if level {
+///             if self.bit < 8 {
+///                 self.byte = self.byte << 1 | self.sda() as u8;
+///             } else if self.transmitting {
+///                 self.send_next = !self.sda();
+///                 let ack = if self.send_next { "MAK" } else { "NMAK" };
+///                 self.events.push(format!("{:#04x} {}", self.data, ack));
+///             }
+///             self.bit += 1;
+///             return;
+///         }
+///
+///         match self.bit {
+///             1..=7 if self.transmitting => self.device_sda = self.data & (0x80 >> self.bit) != 0,
+///             8 if self.transmitting => self.device_sda = true,
+///             8 => {
+///                 let ack = !self.address_phase || ADDRESS_BYTES.contains(&self.byte);
+///                 let sak = if ack { "SAK" } else { "NSAK" };
+///                 self.events.push(format!("{:#04x} {}", self.byte, sak));
+///                 // start transmitting after acknowledging a read address
+///                 self.send_next = self.address_phase && ack && self.byte & 1 == 1;
+///                 self.address_phase = false;
+///                 self.device_sda = !ack;
+///             }
+///             9 => {
+///                 self.bit = 0;
+///                 self.device_sda = true;
+///                 self.transmitting = self.send_next;
+///                 if self.transmitting {
+///                     self.data += 1;
+///                     self.device_sda = self.data & 0x80 != 0;
+///                 }
+///             }
+///             _ => {}
+///         }
+///     }
+/// }
+///
+/// struct Pin<'a> {
+///     bus: &'a RefCell<Bus>,
+///     scl: bool,
+/// }
+///
+/// impl ErrorType for Pin<'_> {
+///     type Error = Infallible;
+/// }
+///
+/// impl InputPin for Pin<'_> {
+///     fn is_high(&self) -> Result<bool, Infallible> {
+///         let mut bus = self.bus.borrow_mut();
+///         if !self.scl {
+///             return Ok(bus.sda());
+///         }
+///         if bus.stretch > 0 {
+///             // the device holds SCL low
+///             bus.stretch -= 1;
+///             return Ok(false);
+///         }
+///         Ok(bus.scl)
+///     }
+///
+///     fn is_low(&self) -> Result<bool, Infallible> {
+///         self.is_high().map(|high| !high)
+///     }
+/// }
+///
+/// impl OpenDrainPin for Pin<'_> {
+///     fn release(&mut self) -> Result<(), Infallible> {
+///         let mut bus = self.bus.borrow_mut();
+///         if self.scl { bus.set_scl(true) } else { bus.set_sda(true) }
+///         Ok(())
+///     }
+///
+///     fn drive_low(&mut self) -> Result<(), Infallible> {
+///         let mut bus = self.bus.borrow_mut();
+///         if self.scl { bus.set_scl(false) } else { bus.set_sda(false) }
+///         Ok(())
+///     }
+/// }
+///
+/// /// A delay adding up the requested delays
+/// struct Delay(u64);
+///
+/// impl DelayNs for Delay {
+///     type Error = Infallible;
+///
+///     fn delay_ns(&mut self, ns: u32) -> Result<(), Infallible> {
+///         self.0 += u64::from(ns);
+///         Ok(())
+///     }
+/// }
+///
+/// let bus = RefCell::new(Bus::new());
+/// let scl = Pin { bus: &bus, scl: true };
+/// let sda = Pin { bus: &bus, scl: false };
+/// // 100 kHz clock
+/// let mut i2c = BitBangI2c::new(scl, sda, Delay(0), 5_000);
+/// let mut events = || bus.borrow_mut().events.drain(..).collect::<Vec<_>>();
+///
+/// let mut buffer = [0; 2];
+/// i2c.write_read(0x50_u8, &[0x01], &mut buffer).unwrap();
+/// assert_eq!(buffer, [0x10, 0x11]);
+/// assert_eq!(
+///     events(),
+///     ["ST", "0xa0 SAK", "0x01 SAK", "SR", "0xa1 SAK", "0x10 MAK", "0x11 NMAK", "SP"]
+/// );
+///
+/// // the device stretches the clock
+/// bus.borrow_mut().stretch = 10;
+/// let mut first = [0; 1];
+/// let mut second = [0; 1];
+/// let mut operations = [Operation::Read(&mut first), Operation::Read(&mut second)];
+/// Transactional::<TenBitAddress>::exec(&mut i2c, 0x134, &mut operations).unwrap();
+/// assert_eq!((first, second), ([0x12], [0x13]));
+/// assert_eq!(
+///     events(),
+///     ["ST", "0xf2 SAK", "0x34 SAK", "SR", "0xf3 SAK", "0x12 MAK", "0x13 NMAK", "SP"]
+/// );
+///
+/// // an empty read, e.g. an SMBus Quick Command, still reads a byte sent by the device
+/// i2c.quick_command(0x50, true).unwrap();
+/// assert_eq!(events(), ["ST", "0xa1 SAK", "0x14 NMAK", "SP"]);
+/// i2c.read(0x50_u8, &mut []).unwrap();
+/// assert_eq!(events(), ["ST", "0xa1 SAK", "0x15 NMAK", "SP"]);
+///
+/// assert_eq!(
+///     i2c.write(0x51_u8, &[0x01]),
+///     Err(BitBangI2cError::NoAcknowledge(NoAcknowledgeSource::Address))
+/// );
+/// assert_eq!(events(), ["ST", "0xa2 NSAK", "SP"]);
+///
+/// // the device holds the clock low for too long
+/// bus.borrow_mut().stretch = u32::MAX;
+/// i2c.set_stretch_timeout(1_000);
+/// assert_eq!(i2c.read(0x50_u8, &mut buffer), Err(BitBangI2cError::ClockStretchTimeout));
+/// ```
+///
+/// Recovering a bus where the device was interrupted while sending a byte:
+///
+/// ```
+/// # use embedded_hal::bitbang::i2c::BitBangI2c;
+/// # use embedded_hal::blocking::delay::DelayNs;
+/// # use embedded_hal::blocking::digital::{ErrorType, InputPin, OpenDrainPin};
+/// # use core::cell::RefCell;
+/// # use core::convert::Infallible;
+/// # struct Bus {
+/// #     scl: bool,
+/// #     master_sda: bool,
+/// #     device_sda: bool,
+/// #     bit: u8,
+/// #     data: u8,
+/// #     clocks: u8,
+/// #     events: Vec<&'static str>,
+/// # }
+/// # impl Bus {
+/// #     fn sda(&self) -> bool {
+/// #         self.master_sda && self.device_sda
+/// #     }
+/// #     fn set_sda(&mut self, level: bool) {
+/// #         let previous = self.sda();
+/// #         self.master_sda = level;
+/// #         if self.scl && !previous && self.sda() {
+/// #             self.events.push("SP");
+/// #         }
+/// #     }
+/// #     fn set_scl(&mut self, level: bool) {
+/// #         if level != self.scl && !level {
+/// #             self.clocks += 1;
+/// #             self.bit += 1;
+/// #             // the device releases SDA once its byte is sent
+/// #             self.device_sda = self.bit >= 8 || self.data & (0x80 >> self.bit) != 0;
+/// #         }
+/// #         self.scl = level;
+/// #     }
+/// # }
+/// # struct Pin<'a> {
+/// #     bus: &'a RefCell<Bus>,
+/// #     scl: bool,
+/// # }
+/// # impl ErrorType for Pin<'_> {
+/// #     type Error = Infallible;
+/// # }
+/// # impl InputPin for Pin<'_> {
+/// #     fn is_high(&self) -> Result<bool, Infallible> {
+/// #         let bus = self.bus.borrow();
+/// #         Ok(if self.scl { bus.scl } else { bus.sda() })
+/// #     }
+/// #     fn is_low(&self) -> Result<bool, Infallible> {
+/// #         self.is_high().map(|high| !high)
+/// #     }
+/// # }
+/// # impl OpenDrainPin for Pin<'_> {
+/// #     fn release(&mut self) -> Result<(), Infallible> {
+/// #         let mut bus = self.bus.borrow_mut();
+/// #         if self.scl { bus.set_scl(true) } else { bus.set_sda(true) }
+/// #         Ok(())
+/// #     }
+/// #     fn drive_low(&mut self) -> Result<(), Infallible> {
+/// #         let mut bus = self.bus.borrow_mut();
+/// #         if self.scl { bus.set_scl(false) } else { bus.set_sda(false) }
+/// #         Ok(())
+/// #     }
+/// # }
+/// # struct Delay;
+/// # impl DelayNs for Delay {
+/// #     type Error = Infallible;
+/// #     fn delay_ns(&mut self, _: u32) -> Result<(), Infallible> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// // the device sent the first 4 bits of 0x00 and holds SDA low
+/// let bus = RefCell::new(Bus {
+///     scl: true,
+///     master_sda: true,
+///     device_sda: false,
+///     bit: 4,
+///     data: 0x00,
+///     clocks: 0,
+///     events: Vec::new(),
+/// });
+/// let scl = Pin { bus: &bus, scl: true };
+/// let sda = Pin { bus: &bus, scl: false };
+/// let mut i2c = BitBangI2c::new(scl, sda, Delay, 5_000);
+///
+/// i2c.recover_bus().unwrap();
+///
+/// let bus = bus.into_inner();
+/// // 4 clock pulses release SDA, then SCL is driven low to send a stop condition
+/// assert_eq!(bus.clocks, 5);
+/// assert_eq!(bus.events, ["SP"]);
+/// ```
+pub struct BitBangI2c<Scl, Sda, Delay> {
+    scl: Scl,
+    sda: Sda,
+    delay: Delay,
+    half_period_ns: u32,
+    stretch_timeout_us: u32,
+}
+
+impl<Scl, Sda, Delay> BitBangI2c<Scl, Sda, Delay> {
+    /// Creates a new `BitBangI2c`
+    ///
+    /// The clock runs with each level lasting at least `half_period_ns` nanoseconds, e.g.
+    /// `5_000` for a 100 kHz clock. Both lines must be released.
+    pub fn new(scl: Scl, sda: Sda, delay: Delay, half_period_ns: u32) -> Self {
+        Self {
+            scl,
+            sda,
+            delay,
+            half_period_ns,
+            stretch_timeout_us: DEFAULT_STRETCH_TIMEOUT_US,
+        }
+    }
+
+    /// Sets how long devices may hold SCL low, in microseconds, before a transaction fails
+    /// with a `ClockStretchTimeout` error
+    pub fn set_stretch_timeout(&mut self, timeout_us: u32) {
+        self.stretch_timeout_us = timeout_us;
+    }
+
+    /// Destroys the I2C and returns its pins and delay
+    pub fn release(self) -> (Scl, Sda, Delay) {
+        (self.scl, self.sda, self.delay)
+    }
+}
+
+impl<Scl, Sda, Delay> BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+{
+    /// Frees a bus where a device holds SDA low, e.g. after a reset of the master in the middle
+    /// of a transaction
+    ///
+    /// Sends up to 9 clock pulses, until the device releases SDA, then a stop condition. Returns
+    /// a `BusStuck` error if SDA is still low after 9 clock pulses.
+    pub fn recover_bus(&mut self) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        self.sda.release().map_err(BitBangI2cError::Pin)?;
+
+        let mut clocks = 0;
+        while self.sda.is_low().map_err(BitBangI2cError::Pin)? {
+            if clocks == 9 {
+                return Err(BitBangI2cError::BusStuck);
+            }
+            self.scl.drive_low().map_err(BitBangI2cError::Pin)?;
+            self.wait_half_period()?;
+            self.release_scl()?;
+            self.wait_half_period()?;
+            clocks += 1;
+        }
+
+        self.scl.drive_low().map_err(BitBangI2cError::Pin)?;
+        self.wait_half_period()?;
+        self.stop()
+    }
+
+    fn wait_half_period(&mut self) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        self.delay
+            .delay_ns(self.half_period_ns)
+            .map_err(BitBangI2cError::Delay)
+    }
+
+    /// Releases SCL and waits until devices stop stretching the clock
+    fn release_scl(&mut self) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        self.scl.release().map_err(BitBangI2cError::Pin)?;
+
+        let poll_ns = core::cmp::max(self.half_period_ns, 1);
+        let timeout_ns = u64::from(self.stretch_timeout_us) * 1_000;
+        let mut stretched_ns = 0;
+        while self.scl.is_low().map_err(BitBangI2cError::Pin)? {
+            if stretched_ns >= timeout_ns {
+                return Err(BitBangI2cError::ClockStretchTimeout);
+            }
+            self.delay
+                .delay_ns(poll_ns)
+                .map_err(BitBangI2cError::Delay)?;
+            stretched_ns += u64::from(poll_ns);
+        }
+
+        Ok(())
+    }
+
+    /// Sends a start or repeated start condition, leaving SCL low
+    fn start(&mut self) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        self.sda.release().map_err(BitBangI2cError::Pin)?;
+        self.wait_half_period()?;
+        self.release_scl()?;
+        if self.sda.is_low().map_err(BitBangI2cError::Pin)? {
+            return Err(BitBangI2cError::ArbitrationLoss);
+        }
+        self.wait_half_period()?;
+        self.sda.drive_low().map_err(BitBangI2cError::Pin)?;
+        self.wait_half_period()?;
+        self.scl.drive_low().map_err(BitBangI2cError::Pin)
+    }
+
+    /// Sends a stop condition, with SCL low
+    fn stop(&mut self) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        self.sda.drive_low().map_err(BitBangI2cError::Pin)?;
+        self.wait_half_period()?;
+        self.release_scl()?;
+        self.wait_half_period()?;
+        self.sda.release().map_err(BitBangI2cError::Pin)?;
+        self.wait_half_period()?;
+        if self.sda.is_low().map_err(BitBangI2cError::Pin)? {
+            return Err(BitBangI2cError::ArbitrationLoss);
+        }
+
+        Ok(())
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        if bit {
+            self.sda.release().map_err(BitBangI2cError::Pin)?;
+        } else {
+            self.sda.drive_low().map_err(BitBangI2cError::Pin)?;
+        }
+        self.wait_half_period()?;
+        self.release_scl()?;
+        if bit && self.sda.is_low().map_err(BitBangI2cError::Pin)? {
+            return Err(BitBangI2cError::ArbitrationLoss);
+        }
+        self.wait_half_period()?;
+        self.scl.drive_low().map_err(BitBangI2cError::Pin)
+    }
+
+    fn read_bit(&mut self) -> Result<bool, BitBangI2cError<Scl::Error, Delay::Error>> {
+        self.sda.release().map_err(BitBangI2cError::Pin)?;
+        self.wait_half_period()?;
+        self.release_scl()?;
+        let bit = self.sda.is_high().map_err(BitBangI2cError::Pin)?;
+        self.wait_half_period()?;
+        self.scl.drive_low().map_err(BitBangI2cError::Pin)?;
+        Ok(bit)
+    }
+
+    /// Writes a byte, returning whether it was acknowledged
+    fn write_byte(&mut self, byte: u8) -> Result<bool, BitBangI2cError<Scl::Error, Delay::Error>> {
+        for i in 0..8 {
+            self.write_bit(byte & (0x80 >> i) != 0)?;
+        }
+
+        self.read_bit().map(|nak| !nak)
+    }
+
+    /// Reads a byte, acknowledging it if `ack` is `true`
+    fn read_byte(&mut self, ack: bool) -> Result<u8, BitBangI2cError<Scl::Error, Delay::Error>> {
+        let mut byte = 0;
+        for _ in 0..8 {
+            byte = (byte << 1) | self.read_bit()? as u8;
+        }
+
+        self.write_bit(!ack)?;
+        Ok(byte)
+    }
+
+    /// Writes an address byte, failing if it is not acknowledged
+    fn write_address_byte(
+        &mut self,
+        byte: u8,
+    ) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        if self.write_byte(byte)? {
+            Ok(())
+        } else {
+            Err(BitBangI2cError::NoAcknowledge(NoAcknowledgeSource::Address))
+        }
+    }
+
+    /// Sends a start condition and the address of the target
+    ///
+    /// `addressed` is `true` if the target was already addressed since the start of the
+    /// transaction.
+    fn address(
+        &mut self,
+        address: Address,
+        read: bool,
+        addressed: bool,
+    ) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        self.start()?;
+        match address {
+            Address::Seven(address) => self.write_address_byte((address << 1) | read as u8),
+            Address::Ten(address) => {
+                let header = 0b1111_0000 | ((address >> 7) as u8 & 0b110);
+                if !(read && addressed) {
+                    self.write_address_byte(header)?;
+                    self.write_address_byte(address as u8)?;
+                    if !read {
+                        return Ok(());
+                    }
+                    self.start()?;
+                }
+                self.write_address_byte(header | 1)
+            }
+        }
+    }
+
+    /// Runs the operations of a transaction, without the final stop condition
+    fn operations(
+        &mut self,
+        address: Address,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        let mut previous_read = None;
+        // whether a byte was read since the last read address
+        let mut byte_read = false;
+        for i in 0..operations.len() {
+            let (operation, next) = operations[i..].split_first_mut().unwrap();
+            let read = matches!(operation, Operation::Read(_));
+            if previous_read != Some(read) {
+                self.address(address, read, previous_read.is_some())?;
+                byte_read = false;
+            }
+            previous_read = Some(read);
+
+            match operation {
+                Operation::Write(bytes) => {
+                    for &byte in bytes.iter() {
+                        if !self.write_byte(byte)? {
+                            return Err(BitBangI2cError::NoAcknowledge(NoAcknowledgeSource::Data));
+                        }
+                    }
+                }
+                Operation::Read(buffer) => {
+                    // the last byte read before SR or SP is not acknowledged
+                    let last_read = next
+                        .iter()
+                        .take_while(|op| matches!(op, Operation::Read(_)))
+                        .all(|op| matches!(op, Operation::Read(b) if b.is_empty()));
+                    let len = buffer.len();
+                    for (j, byte) in buffer.iter_mut().enumerate() {
+                        *byte = self.read_byte(!(last_read && j + 1 == len))?;
+                        byte_read = true;
+                    }
+
+                    // the target may drive SDA until a byte is read
+                    if last_read && !byte_read {
+                        self.read_byte(false)?;
+                        byte_read = true;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs a whole transaction
+    fn transaction(
+        &mut self,
+        address: Address,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), BitBangI2cError<Scl::Error, Delay::Error>> {
+        if operations.is_empty() {
+            return Ok(());
+        }
+
+        match self.operations(address, operations) {
+            Ok(()) => self.stop(),
+            Err(e @ BitBangI2cError::NoAcknowledge(_)) => {
+                // the acknowledge error takes priority over an error sending the stop condition
+                let _ = self.stop();
+                Err(e)
+            }
+            Err(e) => {
+                // leave the bus to the other master or device, ignoring further pin errors
+                let _ = self.scl.release();
+                let _ = self.sda.release();
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<Scl, Sda, Delay> ErrorType for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: digital::ErrorType,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    type Error = BitBangI2cError<Scl::Error, Delay::Error>;
+}
+
+impl<Scl, Sda, Delay> Read<SevenBitAddress> for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn read(&mut self, address: SevenBitAddress, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(Address::Seven(address), &mut [Operation::Read(buffer)])
+    }
+}
+
+impl<Scl, Sda, Delay> Read<TenBitAddress> for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn read(&mut self, address: TenBitAddress, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(Address::Ten(address), &mut [Operation::Read(buffer)])
+    }
+}
+
+impl<Scl, Sda, Delay> Write<SevenBitAddress> for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn write(&mut self, address: SevenBitAddress, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(Address::Seven(address), &mut [Operation::Write(bytes)])
+    }
+}
+
+impl<Scl, Sda, Delay> Write<TenBitAddress> for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn write(&mut self, address: TenBitAddress, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(Address::Ten(address), &mut [Operation::Write(bytes)])
+    }
+}
+
+impl<Scl, Sda, Delay> WriteRead<SevenBitAddress> for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn write_read(
+        &mut self,
+        address: SevenBitAddress,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(
+            Address::Seven(address),
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+}
+
+impl<Scl, Sda, Delay> WriteRead<TenBitAddress> for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn write_read(
+        &mut self,
+        address: TenBitAddress,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.transaction(
+            Address::Ten(address),
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+}
+
+impl<Scl, Sda, Delay> Transactional<SevenBitAddress> for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn exec<'a>(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'a>],
+    ) -> Result<(), Self::Error> {
+        self.transaction(Address::Seven(address), operations)
+    }
+}
+
+impl<Scl, Sda, Delay> Transactional<TenBitAddress> for BitBangI2c<Scl, Sda, Delay>
+where
+    Scl: OpenDrainPin,
+    Sda: OpenDrainPin<Error = Scl::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn exec<'a>(
+        &mut self,
+        address: TenBitAddress,
+        operations: &mut [Operation<'a>],
+    ) -> Result<(), Self::Error> {
+        self.transaction(Address::Ten(address), operations)
+    }
+}
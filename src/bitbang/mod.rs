@@ -0,0 +1,15 @@
+//! Bit-banged implementations
+//!
+//! These types implement bus traits in software on top of the
+//! [digital I/O traits](../blocking/digital/index.html) and a
+//! [delay](../blocking/delay/trait.DelayNs.html), for boards where a peripheral is wired to pins
+//! which are not connected to a suitable hardware peripheral.
+//!
+//! Bit-banging keeps the CPU busy for the whole duration of the bus operations, and its timing
+//! is only as accurate as the delay and the pin accesses allow. Interrupts occurring in the
+//! middle of an operation stretch its timing.
+
+pub mod i2c;
+pub mod onewire;
+pub mod serial;
+pub mod spi;
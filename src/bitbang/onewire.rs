@@ -0,0 +1,232 @@
+//! Bit-banged 1-Wire master
+
+use crate::blocking::delay::DelayNs;
+use crate::blocking::digital::{self, OpenDrainPin};
+use crate::blocking::onewire::{Error, ErrorKind, ErrorType, OneWire};
+
+/// Error type of [`BitBangOneWire`](struct.BitBangOneWire.html)
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BitBangOneWireError<PIN, DELAY> {
+    /// Driving or reading the pin failed
+    Pin(PIN),
+    /// Waiting failed
+    Delay(DELAY),
+    /// The bus was low before a reset pulse
+    BusHeldLow,
+}
+
+impl<PIN, DELAY> Error for BitBangOneWireError<PIN, DELAY>
+where
+    PIN: core::fmt::Debug,
+    DELAY: core::fmt::Debug,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::BusHeldLow => ErrorKind::BusHeldLow,
+            Self::Pin(_) | Self::Delay(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// 1-Wire master bit-banged on an open-drain pin
+///
+/// This type implements [`OneWire`](../../blocking/onewire/trait.OneWire.html) with the
+/// standard speed timings recommended by Maxim's application note 126. The pin must be
+/// connected to a pull-up resistor.
+///
+/// Interrupts occurring during a time slot can corrupt it: consider disabling them while
+/// talking to devices.
+///
+/// # Examples
+///
+/// Reading the ROM code of a simulated device:
+///
+/// ```
+/// use embedded_hal::bitbang::onewire::BitBangOneWire;
+/// use embedded_hal::blocking::delay::DelayNs;
+/// use embedded_hal::blocking::digital::{ErrorType, InputPin, OpenDrainPin};
+/// use embedded_hal::blocking::onewire::{OneWire, Rom};
+/// use core::cell::RefCell;
+/// use core::convert::Infallible;
+///
+/// const ROM: Rom = Rom([0x28, 0xFF, 0x64, 0x1E, 0x0C, 0x3C, 0x04, 0xD1]);
+///
+/// /// A bus with a single device answering the read ROM command, timed in microseconds
+/// struct Bus {
+///     now: u32,
+///     master_low_since: Option<u32>,
+///     device_low: core::ops::Range<u32>,
+///     received: u8,
+///     received_bits: u32,
+///     sent_bits: u32,
+/// }
+///
+/// impl Bus {
+///     fn release(&mut self) {
+///         let since = match self.master_low_since.take() {
+///             Some(since) => since,
+///             None => return,
+///         };
+///         match self.now - since {
+///             480..=960 => {
+///                 // reset pulse, answered by a presence pulse
+///                 self.device_low = self.now + 30..self.now + 150;
+///                 self.received_bits = 0;
+///                 self.sent_bits = 0;
+///             }
+///             1..=15 if self.received_bits == 8 && self.received == 0x33 => {
+///                 // read time slot, the device holds the bus low to send a 0
+///                 let byte = ROM.0[self.sent_bits as usize / 8];
+///                 if byte & (1 << (self.sent_bits % 8)) == 0 {
+///                     self.device_low = since..since + 30;
+///                 }
+///                 self.sent_bits += 1;
+///             }
+///             duration => {
+///                 // write time slot, sampled by the device 30 us after it started
+///                 let bit = (duration < 30) as u8;
+///                 self.received = (self.received >> 1) | (bit << 7);
+///                 self.received_bits += 1;
+///             }
+///         }
+///     }
+///
+///     fn is_high(&self) -> bool {
+///         self.master_low_since.is_none() && !self.device_low.contains(&self.now)
+///     }
+/// }
+///
+/// struct Pin<'a>(&'a RefCell<Bus>);
+///
+/// impl ErrorType for Pin<'_> {
+///     type Error = Infallible;
+/// }
+///
+/// impl InputPin for Pin<'_> {
+///     fn is_high(&self) -> Result<bool, Infallible> {
+///         Ok(self.0.borrow().is_high())
+///     }
+///
+///     fn is_low(&self) -> Result<bool, Infallible> {
+///         self.is_high().map(|high| !high)
+///     }
+/// }
+///
+/// impl OpenDrainPin for Pin<'_> {
+///     fn release(&mut self) -> Result<(), Infallible> {
+///         self.0.borrow_mut().release();
+///         Ok(())
+///     }
+///
+///     fn drive_low(&mut self) -> Result<(), Infallible> {
+///         let mut bus = self.0.borrow_mut();
+///         bus.master_low_since = Some(bus.now);
+///         Ok(())
+///     }
+/// }
+///
+/// struct Delay<'a>(&'a RefCell<Bus>);
+///
+/// impl DelayNs for Delay<'_> {
+///     type Error = Infallible;
+///
+///     fn delay_ns(&mut self, ns: u32) -> Result<(), Infallible> {
+///         self.0.borrow_mut().now += ns / 1_000;
+///         Ok(())
+///     }
+/// }
+///
+/// let bus = RefCell::new(Bus {
+///     now: 0,
+///     master_low_since: None,
+///     device_low: 0..0,
+///     received: 0,
+///     received_bits: 0,
+///     sent_bits: 0,
+/// });
+/// let mut onewire = BitBangOneWire::new(Pin(&bus), Delay(&bus));
+///
+/// assert_eq!(onewire.reset(), Ok(true));
+/// assert_eq!(onewire.read_rom(), Ok(ROM));
+/// ```
+pub struct BitBangOneWire<P, D> {
+    pin: P,
+    delay: D,
+}
+
+impl<P, D> BitBangOneWire<P, D> {
+    /// Creates a new `BitBangOneWire`
+    pub fn new(pin: P, delay: D) -> Self {
+        Self { pin, delay }
+    }
+
+    /// Destroys the 1-Wire master and returns its pin and delay
+    pub fn release(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+impl<P, D> BitBangOneWire<P, D>
+where
+    P: OpenDrainPin,
+    D: DelayNs,
+{
+    fn wait_us(&mut self, us: u32) -> Result<(), BitBangOneWireError<P::Error, D::Error>> {
+        self.delay.delay_us(us).map_err(BitBangOneWireError::Delay)
+    }
+
+    /// Drives the bus low for `low_us` microseconds, then releases it for `high_us` microseconds
+    fn pulse(
+        &mut self,
+        low_us: u32,
+        high_us: u32,
+    ) -> Result<(), BitBangOneWireError<P::Error, D::Error>> {
+        self.pin.drive_low().map_err(BitBangOneWireError::Pin)?;
+        self.wait_us(low_us)?;
+        self.pin.release().map_err(BitBangOneWireError::Pin)?;
+        self.wait_us(high_us)
+    }
+}
+
+impl<P, D> ErrorType for BitBangOneWire<P, D>
+where
+    P: digital::ErrorType,
+    D: DelayNs,
+    D::Error: core::fmt::Debug,
+{
+    type Error = BitBangOneWireError<P::Error, D::Error>;
+}
+
+impl<P, D> OneWire for BitBangOneWire<P, D>
+where
+    P: OpenDrainPin,
+    D: DelayNs,
+    D::Error: core::fmt::Debug,
+{
+    fn reset(&mut self) -> Result<bool, Self::Error> {
+        self.pin.release().map_err(BitBangOneWireError::Pin)?;
+        if self.pin.is_low().map_err(BitBangOneWireError::Pin)? {
+            return Err(BitBangOneWireError::BusHeldLow);
+        }
+
+        self.pulse(480, 70)?;
+        let presence = self.pin.is_low().map_err(BitBangOneWireError::Pin)?;
+        self.wait_us(410)?;
+        Ok(presence)
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<(), Self::Error> {
+        if bit {
+            self.pulse(6, 64)
+        } else {
+            self.pulse(60, 10)
+        }
+    }
+
+    fn read_bit(&mut self) -> Result<bool, Self::Error> {
+        self.pulse(6, 9)?;
+        let bit = self.pin.is_high().map_err(BitBangOneWireError::Pin)?;
+        self.wait_us(55)?;
+        Ok(bit)
+    }
+}
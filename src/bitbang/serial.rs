@@ -0,0 +1,487 @@
+//! Bit-banged serial interface
+
+use crate::blocking::digital::{self, InputPin, OutputPin, PinState};
+use crate::blocking::serial::{Error, ErrorKind, ErrorType};
+use crate::nb::serial::{Read, Write};
+use crate::nb::timer::{CountDown, Periodic};
+
+/// Number of data bits of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataBits {
+    /// 5 data bits
+    Five,
+    /// 6 data bits
+    Six,
+    /// 7 data bits
+    Seven,
+    /// 8 data bits
+    Eight,
+}
+
+/// Parity bit of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    /// No parity bit
+    None,
+    /// Parity bit set if the data bits contain an odd number of ones
+    Even,
+    /// Parity bit set if the data bits contain an even number of ones
+    Odd,
+}
+
+/// Number of stop bits of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopBits {
+    /// 1 stop bit
+    One,
+    /// 2 stop bits
+    Two,
+}
+
+/// Configuration of a [`SoftSerial`](struct.SoftSerial.html)
+///
+/// The default configuration is 8 data bits, no parity and 1 stop bit (8N1), with 4 timer ticks
+/// per bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Number of data bits
+    pub data_bits: DataBits,
+    /// Parity bit
+    pub parity: Parity,
+    /// Number of stop bits
+    pub stop_bits: StopBits,
+    /// Number of timer ticks per bit, at least 1
+    ///
+    /// The received bits are sampled in the middle of the bit period, which is only accurate to
+    /// the tick: more ticks per bit make the reception more reliable.
+    pub ticks_per_bit: u8,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            ticks_per_bit: 4,
+        }
+    }
+}
+
+impl Config {
+    fn data_bits(&self) -> u8 {
+        match self.data_bits {
+            DataBits::Five => 5,
+            DataBits::Six => 6,
+            DataBits::Seven => 7,
+            DataBits::Eight => 8,
+        }
+    }
+
+    /// Returns the parity bit of `word`, if any
+    fn parity_bit(&self, word: u8) -> Option<bool> {
+        let odd_ones = word.count_ones() % 2 == 1;
+        match self.parity {
+            Parity::None => None,
+            Parity::Even => Some(odd_ones),
+            Parity::Odd => Some(!odd_ones),
+        }
+    }
+}
+
+/// Error type of [`SoftSerial`](struct.SoftSerial.html)
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SoftSerialError<PIN, TIMER> {
+    /// Driving or reading a pin failed
+    Pin(PIN),
+    /// Waiting for the timer failed
+    Timer(TIMER),
+    /// A word was received before the previous one was read
+    Overrun,
+    /// The stop bit of a received word was not high
+    Framing,
+    /// The parity bit of a received word was wrong
+    Parity,
+}
+
+impl<PIN, TIMER> Error for SoftSerialError<PIN, TIMER>
+where
+    PIN: core::fmt::Debug,
+    TIMER: core::fmt::Debug,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Overrun => ErrorKind::Overrun,
+            Self::Framing => ErrorKind::Framing,
+            Self::Parity => ErrorKind::Parity,
+            Self::Pin(_) | Self::Timer(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// State of the reception of a frame
+#[derive(Clone, Copy)]
+struct Reception {
+    /// Number of bits sampled, including the start bit
+    bits: u8,
+    /// Bits sampled after the start bit, first bit in the least significant bit
+    frame: u16,
+    /// Number of ticks before the next sample
+    ticks: u8,
+}
+
+/// Error of a received frame, reported when the word is read
+#[derive(Clone, Copy)]
+enum ReceiveError {
+    Overrun,
+    Framing,
+    Parity,
+}
+
+/// Serial interface bit-banged on a TX and an RX pin, timed by a periodic timer
+///
+/// This type implements the [`nb::serial`] `Read<u8>` and `Write<u8>` traits. Frames start
+/// with a low start bit, followed by the data bits (least significant bit first), the optional
+/// parity bit and high stop bits, as set in the [`Config`](struct.Config.html).
+///
+/// The timer must be started beforehand as a periodic timer ticking `ticks_per_bit` times per
+/// bit, e.g. at 38.4 kHz for 9600 baud with the default 4 ticks per bit. Transmission and
+/// reception only progress when the `Read` and `Write` methods are called: they must be called
+/// at least once per tick, e.g. by a busy loop with `block!` or from the timer interrupt. Ticks
+/// which are missed delay the transmission and can corrupt the reception. Each call handles at
+/// most the ticks reported by the timer, so words being received are not lost while waiting to
+/// transmit and vice versa.
+///
+/// A word received before the previous one was read is reported as an `Overrun` error. Words
+/// with a low stop bit or a wrong parity bit are reported as `Framing` and `Parity` errors.
+///
+/// [`nb::serial`]: ../../nb/serial/index.html
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::bitbang::serial::{Config, DataBits, Parity, SoftSerial, SoftSerialError};
+/// use embedded_hal::blocking::digital::{ErrorType, InputPin, OutputPin};
+/// use embedded_hal::nb::serial::{Read, Write};
+/// use embedded_hal::nb::timer::{self, CountDown, Periodic};
+/// use core::cell::Cell;
+/// use core::convert::Infallible;
+///
+/// /// A pin driving or reading a line
+/// struct Pin<'a>(&'a Cell<bool>);
+///
+/// impl ErrorType for Pin<'_> {
+///     type Error = Infallible;
+/// }
+///
+/// impl OutputPin for Pin<'_> {
+///     fn set_low(&mut self) -> Result<(), Infallible> {
+///         self.0.set(false);
+///         Ok(())
+///     }
+///
+///     fn set_high(&mut self) -> Result<(), Infallible> {
+///         self.0.set(true);
+///         Ok(())
+///     }
+/// }
+///
+/// impl InputPin for Pin<'_> {
+///     fn is_high(&self) -> Result<bool, Infallible> {
+///         Ok(self.0.get())
+///     }
+///
+///     fn is_low(&self) -> Result<bool, Infallible> {
+///         Ok(!self.0.get())
+///     }
+/// }
+///
+/// /// A periodic timer ticking every other time it is polled
+/// struct Timer(bool);
+///
+/// impl timer::ErrorType for Timer {
+///     type Error = Infallible;
+/// }
+///
+/// impl CountDown for Timer {
+///     type Time = ();
+///
+///     fn start<T: Into<()>>(&mut self, _: T) -> Result<(), Infallible> {
+///         Ok(())
+///     }
+///
+///     fn wait(&mut self) -> nb::Result<(), Infallible> {
+///         self.0 = !self.0;
+///         if self.0 { Ok(()) } else { Err(nb::Error::WouldBlock) }
+///     }
+/// }
+///
+/// impl Periodic for Timer {}
+///
+/// // TX and RX connected together
+/// let line = Cell::new(true);
+/// let mut serial = SoftSerial::new(Pin(&line), Pin(&line), Timer(false), Config::default())
+///     .unwrap();
+///
+/// for &word in b"Hi" {
+///     nb::block!(serial.write(word)).unwrap();
+///     assert_eq!(nb::block!(serial.read()), Ok(word));
+/// }
+///
+/// // a transmitter sending 8 data bits and an even parity bit
+/// let config = Config { parity: Parity::Even, ..Config::default() };
+/// let idle = Cell::new(true);
+/// let mut transmitter = SoftSerial::new(Pin(&line), Pin(&idle), Timer(false), config).unwrap();
+///
+/// // receivers expecting an odd parity bit, and 7 data bits without parity bit
+/// let config = Config { parity: Parity::Odd, ..Config::default() };
+/// let mut odd = SoftSerial::new(Pin(&idle), Pin(&line), Timer(false), config).unwrap();
+/// let config = Config { data_bits: DataBits::Seven, ..Config::default() };
+/// let mut seven = SoftSerial::new(Pin(&idle), Pin(&line), Timer(false), config).unwrap();
+///
+/// let mut transfer = |word| {
+///     nb::block!(transmitter.write(word)).unwrap();
+///     let (mut odd_result, mut seven_result) = (None, None);
+///     while odd_result.is_none() || seven_result.is_none() {
+///         let _ = transmitter.flush();
+///         for (receiver, result) in [(&mut odd, &mut odd_result), (&mut seven, &mut seven_result)]
+///             .iter_mut()
+///         {
+///             if result.is_none() {
+///                 match receiver.read() {
+///                     Err(nb::Error::WouldBlock) => {}
+///                     Err(nb::Error::Other(e)) => **result = Some(Err(e)),
+///                     Ok(word) => **result = Some(Ok(word)),
+///                 }
+///             }
+///         }
+///     }
+///     (odd_result.unwrap(), seven_result.unwrap())
+/// };
+///
+/// // the 8th data bit takes the place of the stop bit of the 7 data bits receiver
+/// assert_eq!(transfer(b'A' | 0x80), (Err(SoftSerialError::Parity), Ok(b'A')));
+/// assert_eq!(transfer(b'A'), (Err(SoftSerialError::Parity), Err(SoftSerialError::Framing)));
+/// ```
+pub struct SoftSerial<Tx, Rx, Timer> {
+    tx: Tx,
+    rx: Rx,
+    timer: Timer,
+    config: Config,
+    /// Bits left to send, first bit in the least significant bit
+    tx_frame: u16,
+    /// Number of bits left to send
+    tx_bits: u8,
+    /// Number of ticks left before sending the next bit
+    tx_ticks: u8,
+    reception: Option<Reception>,
+    received: Option<Result<u8, ReceiveError>>,
+}
+
+impl<Tx, Rx, Timer> SoftSerial<Tx, Rx, Timer>
+where
+    Tx: OutputPin,
+    Rx: InputPin<Error = Tx::Error>,
+    Timer: CountDown + Periodic,
+{
+    /// Creates a new `SoftSerial`, driving the TX line high (idle)
+    ///
+    /// `timer` must already be running, see the [type documentation](struct.SoftSerial.html).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config.ticks_per_bit` is 0.
+    pub fn new(
+        mut tx: Tx,
+        rx: Rx,
+        timer: Timer,
+        config: Config,
+    ) -> Result<Self, SoftSerialError<Tx::Error, Timer::Error>> {
+        assert!(config.ticks_per_bit > 0, "a bit lasts at least one tick");
+        tx.set_high().map_err(SoftSerialError::Pin)?;
+
+        Ok(Self {
+            tx,
+            rx,
+            timer,
+            config,
+            tx_frame: 0,
+            tx_bits: 0,
+            tx_ticks: 0,
+            reception: None,
+            received: None,
+        })
+    }
+
+    /// Destroys the serial interface and returns its pins and timer
+    pub fn release(self) -> (Tx, Rx, Timer) {
+        (self.tx, self.rx, self.timer)
+    }
+
+    fn tx_busy(&self) -> bool {
+        self.tx_bits > 0 || self.tx_ticks > 0
+    }
+
+    /// Handles the ticks of the timer which elapsed since the last call
+    fn poll(&mut self) -> Result<(), SoftSerialError<Tx::Error, Timer::Error>> {
+        loop {
+            match self.timer.wait() {
+                Ok(()) => self.tick()?,
+                Err(nb::Error::WouldBlock) => return Ok(()),
+                Err(nb::Error::Other(e)) => return Err(SoftSerialError::Timer(e)),
+            }
+        }
+    }
+
+    fn tick(&mut self) -> Result<(), SoftSerialError<Tx::Error, Timer::Error>> {
+        self.tick_tx()?;
+        self.tick_rx()
+    }
+
+    fn tick_tx(&mut self) -> Result<(), SoftSerialError<Tx::Error, Timer::Error>> {
+        if self.tx_ticks > 0 {
+            self.tx_ticks -= 1;
+        }
+
+        if self.tx_ticks == 0 && self.tx_bits > 0 {
+            let bit = PinState::from(self.tx_frame & 1 != 0);
+            self.tx.set_state(bit).map_err(SoftSerialError::Pin)?;
+            self.tx_frame >>= 1;
+            self.tx_bits -= 1;
+            self.tx_ticks = self.config.ticks_per_bit;
+        }
+
+        Ok(())
+    }
+
+    fn tick_rx(&mut self) -> Result<(), SoftSerialError<Tx::Error, Timer::Error>> {
+        let mut reception = match self.reception {
+            Some(reception) => reception,
+            None if self.rx.is_low().map_err(SoftSerialError::Pin)? => {
+                // sample the start bit half a bit later
+                Reception {
+                    bits: 0,
+                    frame: 0,
+                    ticks: self.config.ticks_per_bit / 2,
+                }
+            }
+            None => return Ok(()),
+        };
+
+        if reception.ticks > 0 {
+            reception.ticks -= 1;
+            self.reception = Some(reception);
+            return Ok(());
+        }
+
+        let high = self.rx.is_high().map_err(SoftSerialError::Pin)?;
+        if reception.bits == 0 && high {
+            // glitch on the line rather than a start bit
+            self.reception = None;
+            return Ok(());
+        }
+        if reception.bits > 0 && high {
+            reception.frame |= 1 << (reception.bits - 1);
+        }
+        reception.bits += 1;
+        reception.ticks = self.config.ticks_per_bit - 1;
+
+        let data_bits = self.config.data_bits();
+        let parity_bits = self.config.parity_bit(0).map_or(0, |_| 1);
+        if reception.bits < 1 + data_bits + parity_bits + 1 {
+            self.reception = Some(reception);
+            return Ok(());
+        }
+
+        // the stop bit has been sampled
+        self.reception = None;
+        let word = (reception.frame & ((1 << data_bits) - 1)) as u8;
+        let parity = reception.frame & (1 << data_bits) != 0;
+        let stop = reception.frame & (1 << (data_bits + parity_bits)) != 0;
+
+        let result = if self.received.is_some() {
+            Err(ReceiveError::Overrun)
+        } else if !stop {
+            Err(ReceiveError::Framing)
+        } else if self.config.parity_bit(word) == Some(!parity) {
+            Err(ReceiveError::Parity)
+        } else {
+            Ok(word)
+        };
+        self.received = Some(result);
+
+        Ok(())
+    }
+}
+
+impl<Tx, Rx, Timer> ErrorType for SoftSerial<Tx, Rx, Timer>
+where
+    Tx: digital::ErrorType,
+    Timer: CountDown,
+{
+    type Error = SoftSerialError<Tx::Error, Timer::Error>;
+}
+
+impl<Tx, Rx, Timer> Read<u8> for SoftSerial<Tx, Rx, Timer>
+where
+    Tx: OutputPin,
+    Rx: InputPin<Error = Tx::Error>,
+    Timer: CountDown + Periodic,
+{
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.poll()?;
+
+        match self.received.take() {
+            Some(Ok(word)) => Ok(word),
+            Some(Err(ReceiveError::Overrun)) => Err(nb::Error::Other(SoftSerialError::Overrun)),
+            Some(Err(ReceiveError::Framing)) => Err(nb::Error::Other(SoftSerialError::Framing)),
+            Some(Err(ReceiveError::Parity)) => Err(nb::Error::Other(SoftSerialError::Parity)),
+            None => Err(nb::Error::WouldBlock),
+        }
+    }
+}
+
+impl<Tx, Rx, Timer> Write<u8> for SoftSerial<Tx, Rx, Timer>
+where
+    Tx: OutputPin,
+    Rx: InputPin<Error = Tx::Error>,
+    Timer: CountDown + Periodic,
+{
+    /// Starts sending `word` once the previous word has been sent
+    ///
+    /// The data bits of `word` beyond the configured number of data bits are ignored.
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.poll()?;
+        if self.tx_busy() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let data_bits = self.config.data_bits();
+        let word = word & ((1u16 << data_bits) - 1) as u8;
+        // the start bit is the low least significant bit
+        let mut frame = u16::from(word) << 1;
+        let mut bits = 1 + data_bits;
+        if let Some(parity) = self.config.parity_bit(word) {
+            frame |= u16::from(parity) << bits;
+            bits += 1;
+        }
+        let stop_bits = match self.config.stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        };
+        frame |= ((1 << stop_bits) - 1) << bits;
+
+        self.tx_frame = frame;
+        self.tx_bits = bits + stop_bits;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.poll()?;
+        if self.tx_busy() {
+            Err(nb::Error::WouldBlock)
+        } else {
+            Ok(())
+        }
+    }
+}
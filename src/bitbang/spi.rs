@@ -0,0 +1,455 @@
+//! Bit-banged SPI master
+
+use crate::blocking::delay::DelayNs;
+use crate::blocking::digital::{self, InputPin, OutputPin, PinState};
+use crate::blocking::spi::{
+    Error, ErrorKind, ErrorType, Operation, SpiBus, Transactional, Transfer, Write, WriteIter,
+};
+use crate::nb::spi::{FullDuplex, Mode, Phase, Polarity};
+
+/// Order in which the bits of a word are sent and received
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Most significant bit first
+    MsbFirst,
+    /// Least significant bit first
+    LsbFirst,
+}
+
+/// Error type of [`BitBangSpi`](struct.BitBangSpi.html)
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BitBangSpiError<PIN, DELAY> {
+    /// Driving or reading a pin failed
+    Pin(PIN),
+    /// Waiting for half a clock period failed
+    Delay(DELAY),
+    /// A word was written with `FullDuplex` before the word received previously was read
+    Overrun,
+}
+
+impl<PIN, DELAY> Error for BitBangSpiError<PIN, DELAY>
+where
+    PIN: core::fmt::Debug,
+    DELAY: core::fmt::Debug,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Overrun => ErrorKind::Overrun,
+            Self::Pin(_) | Self::Delay(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// SPI master bit-banged on an SCK, a MOSI and a MISO pin
+///
+/// This type implements [`FullDuplex<u8>`] and all the blocking SPI traits, including
+/// [`SpiBus<u8>`], in any of the four SPI modes. Each bit takes a full clock period, i.e. two
+/// calls to `delay_ns` with the half period given to [`new`] plus the time spent accessing the
+/// pins.
+///
+/// Each word starts with SCK at its idle level. With `CaptureOnFirstTransition` phase (modes 0
+/// and 2), MOSI is set half a period before the leading clock edge; with
+/// `CaptureOnSecondTransition` phase (modes 1 and 3), MOSI is set on the leading clock edge. MISO
+/// is sampled on the capture edge in both cases.
+///
+/// With `FullDuplex`, a word written before the word received previously was read is reported
+/// as an `Overrun` error by the next `read`.
+///
+/// Chip select is not handled by this type: use it with an
+/// [`SpiDevice`](../../blocking/spi/trait.SpiDevice.html) implementation such as
+/// [`ExclusiveDevice`](../../blocking/spi/struct.ExclusiveDevice.html).
+///
+/// [`FullDuplex<u8>`]: ../../nb/spi/trait.FullDuplex.html
+/// [`SpiBus<u8>`]: ../../blocking/spi/trait.SpiBus.html
+/// [`new`]: #method.new
+///
+/// # Examples
+///
+/// Talking to a simulated slave in all modes and bit orders:
+///
+/// ```
+/// use embedded_hal::bitbang::spi::{BitBangSpi, BitOrder};
+/// use embedded_hal::blocking::delay::DelayNs;
+/// use embedded_hal::blocking::digital::{ErrorType, InputPin, OutputPin};
+/// use embedded_hal::blocking::spi::Transfer;
+/// use embedded_hal::nb::spi::{Mode, Phase, Polarity, MODE_0, MODE_1, MODE_2, MODE_3};
+/// use core::cell::RefCell;
+/// use core::convert::Infallible;
+/// use std::collections::VecDeque;
+/// use std::rc::Rc;
+///
+/// /// An SPI slave shifting words in and out on the clock edges
+/// struct Slave {
+///     mode: Mode,
+///     lsb_first: bool,
+///     sck: bool,
+///     mosi: bool,
+///     miso: bool,
+///     bit: u8,
+///     rx: u8,
+///     tx: u8,
+///     received: Vec<u8>,
+///     responses: VecDeque<u8>,
+/// }
+///
+/// impl Slave {
+///     fn new(mode: Mode, lsb_first: bool, responses: &[u8]) -> Self {
+///         let mut slave = Slave {
+///             mode,
+///             lsb_first,
+///             sck: mode.polarity == Polarity::IdleHigh,
+///             mosi: false,
+///             miso: false,
+///             bit: 0,
+///             rx: 0,
+///             tx: 0,
+///             received: Vec::new(),
+///             responses: responses.iter().copied().collect(),
+///         };
+///         if mode.phase == Phase::CaptureOnFirstTransition {
+///             // the first bit must be presented before the first clock edge
+///             slave.shift_out();
+///         }
+///         slave
+///     }
+///
+///     fn mask(&self) -> u8 {
+///         if self.lsb_first { 1 << self.bit } else { 0x80 >> self.bit }
+///     }
+///
+///     fn shift_out(&mut self) {
+///         if self.bit == 0 {
+///             self.tx = self.responses.pop_front().unwrap_or(0xFF);
+///         }
+///         self.miso = self.tx & self.mask() != 0;
+///     }
+///
+///     fn set_sck(&mut self, sck: bool) {
+///         if sck == self.sck {
+///             return;
+///         }
+///         self.sck = sck;
+///
+///         let leading = sck != (self.mode.polarity == Polarity::IdleHigh);
+///         let first_transition = self.mode.phase == Phase::CaptureOnFirstTransition;
+///         if leading == first_transition {
+///             // capture edge
+///             if self.mosi {
+///                 self.rx |= self.mask();
+///             }
+///             self.bit = (self.bit + 1) % 8;
+///             if self.bit == 0 {
+///                 self.received.push(self.rx);
+///                 self.rx = 0;
+///             }
+///         } else {
+///             self.shift_out();
+///         }
+///     }
+/// }
+///
+/// struct Pin<'a>(&'a RefCell<Slave>, fn(&mut Slave, bool));
+///
+/// impl ErrorType for Pin<'_> {
+///     type Error = Infallible;
+/// }
+///
+/// impl OutputPin for Pin<'_> {
+///     fn set_low(&mut self) -> Result<(), Infallible> {
+///         (self.1)(&mut self.0.borrow_mut(), false);
+///         Ok(())
+///     }
+///
+///     fn set_high(&mut self) -> Result<(), Infallible> {
+///         (self.1)(&mut self.0.borrow_mut(), true);
+///         Ok(())
+///     }
+/// }
+///
+/// impl InputPin for Pin<'_> {
+///     fn is_high(&self) -> Result<bool, Infallible> {
+///         Ok(self.0.borrow().miso)
+///     }
+///
+///     fn is_low(&self) -> Result<bool, Infallible> {
+///         self.is_high().map(|high| !high)
+///     }
+/// }
+///
+/// /// A delay adding up the requested delays
+/// struct Delay(u32);
+///
+/// impl DelayNs for Delay {
+///     type Error = Infallible;
+///
+///     fn delay_ns(&mut self, ns: u32) -> Result<(), Infallible> {
+///         self.0 += ns;
+///         Ok(())
+///     }
+/// }
+///
+/// for &mode in &[MODE_0, MODE_1, MODE_2, MODE_3] {
+///     for &bit_order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+///         let lsb_first = bit_order == BitOrder::LsbFirst;
+///         let slave = RefCell::new(Slave::new(mode, lsb_first, &[0xA5, 0x3C]));
+///
+///         let sck = Pin(&slave, Slave::set_sck);
+///         let mosi = Pin(&slave, |slave, level| slave.mosi = level);
+///         let miso = Pin(&slave, |_, _| unreachable!());
+///         // 100 kHz clock
+///         let mut spi = BitBangSpi::new(sck, mosi, miso, Delay(0), mode, 5_000);
+///         spi.set_bit_order(bit_order);
+///
+///         let mut words = [0x81, 0x42];
+///         assert_eq!(spi.transfer(&mut words).unwrap(), [0xA5, 0x3C]);
+///
+///         let (_, _, _, delay) = spi.release();
+///         assert_eq!(delay.0, 2 * 8 * 10_000);
+///         assert_eq!(slave.into_inner().received, [0x81, 0x42]);
+///     }
+/// }
+/// ```
+pub struct BitBangSpi<Sck, Mosi, Miso, Delay> {
+    sck: Sck,
+    mosi: Mosi,
+    miso: Miso,
+    delay: Delay,
+    mode: Mode,
+    bit_order: BitOrder,
+    half_period_ns: u32,
+    received: Option<u8>,
+    /// Whether a word was received with `FullDuplex` before the previous one was read
+    overrun: bool,
+}
+
+impl<Sck, Mosi, Miso, Delay> BitBangSpi<Sck, Mosi, Miso, Delay> {
+    /// Creates a new `BitBangSpi`
+    ///
+    /// The clock runs in the given `mode`, with each clock level lasting at least
+    /// `half_period_ns` nanoseconds. Words are sent most significant bit first, see
+    /// [`set_bit_order`](#method.set_bit_order) to change it.
+    pub fn new(
+        sck: Sck,
+        mosi: Mosi,
+        miso: Miso,
+        delay: Delay,
+        mode: Mode,
+        half_period_ns: u32,
+    ) -> Self {
+        Self {
+            sck,
+            mosi,
+            miso,
+            delay,
+            mode,
+            bit_order: BitOrder::MsbFirst,
+            half_period_ns,
+            received: None,
+            overrun: false,
+        }
+    }
+
+    /// Sets the order in which the bits of the next words are sent and received
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.bit_order = bit_order;
+    }
+
+    /// Destroys the SPI and returns its pins and delay
+    pub fn release(self) -> (Sck, Mosi, Miso, Delay) {
+        (self.sck, self.mosi, self.miso, self.delay)
+    }
+}
+
+impl<Sck, Mosi, Miso, Delay> BitBangSpi<Sck, Mosi, Miso, Delay>
+where
+    Sck: OutputPin,
+    Mosi: OutputPin<Error = Sck::Error>,
+    Miso: InputPin<Error = Sck::Error>,
+    Delay: DelayNs,
+{
+    /// Drives SCK to its active level if `active` is `true`, to its idle level otherwise
+    fn set_sck(&mut self, active: bool) -> Result<(), BitBangSpiError<Sck::Error, Delay::Error>> {
+        let idle_high = self.mode.polarity == Polarity::IdleHigh;
+        self.sck
+            .set_state(PinState::from(active != idle_high))
+            .map_err(BitBangSpiError::Pin)
+    }
+
+    fn wait_half_period(&mut self) -> Result<(), BitBangSpiError<Sck::Error, Delay::Error>> {
+        self.delay
+            .delay_ns(self.half_period_ns)
+            .map_err(BitBangSpiError::Delay)
+    }
+
+    /// Clocks `word` out on MOSI while clocking a word in from MISO
+    fn transfer_word(&mut self, word: u8) -> Result<u8, BitBangSpiError<Sck::Error, Delay::Error>> {
+        self.set_sck(false)?;
+
+        let mut received = 0;
+        for i in 0..8 {
+            let mask = match self.bit_order {
+                BitOrder::MsbFirst => 0x80 >> i,
+                BitOrder::LsbFirst => 1 << i,
+            };
+            let bit = PinState::from(word & mask != 0);
+
+            match self.mode.phase {
+                Phase::CaptureOnFirstTransition => {
+                    self.mosi.set_state(bit).map_err(BitBangSpiError::Pin)?;
+                    self.wait_half_period()?;
+                    self.set_sck(true)?;
+                    if self.miso.is_high().map_err(BitBangSpiError::Pin)? {
+                        received |= mask;
+                    }
+                    self.wait_half_period()?;
+                    self.set_sck(false)?;
+                }
+                Phase::CaptureOnSecondTransition => {
+                    self.set_sck(true)?;
+                    self.mosi.set_state(bit).map_err(BitBangSpiError::Pin)?;
+                    self.wait_half_period()?;
+                    self.set_sck(false)?;
+                    if self.miso.is_high().map_err(BitBangSpiError::Pin)? {
+                        received |= mask;
+                    }
+                    self.wait_half_period()?;
+                }
+            }
+        }
+
+        Ok(received)
+    }
+}
+
+impl<Sck, Mosi, Miso, Delay> ErrorType for BitBangSpi<Sck, Mosi, Miso, Delay>
+where
+    Sck: digital::ErrorType,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    type Error = BitBangSpiError<Sck::Error, Delay::Error>;
+}
+
+impl<Sck, Mosi, Miso, Delay> FullDuplex<u8> for BitBangSpi<Sck, Mosi, Miso, Delay>
+where
+    Sck: OutputPin,
+    Mosi: OutputPin<Error = Sck::Error>,
+    Miso: InputPin<Error = Sck::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    /// Returns the word received while writing the last word, or `WouldBlock` if it has already
+    /// been read
+    ///
+    /// If a word was written before the word received previously was read, both words are
+    /// discarded and an `Overrun` error is returned instead.
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        if self.overrun {
+            self.overrun = false;
+            self.received = None;
+            return Err(nb::Error::Other(BitBangSpiError::Overrun));
+        }
+
+        self.received.take().ok_or(nb::Error::WouldBlock)
+    }
+
+    /// Transfers a whole word before returning
+    ///
+    /// The word received previously should be read first, otherwise the next `read` reports an
+    /// `Overrun` error.
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        let received = self.transfer_word(word)?;
+        self.overrun |= self.received.is_some();
+        self.received = Some(received);
+        Ok(())
+    }
+}
+
+impl<Sck, Mosi, Miso, Delay> Transfer<u8> for BitBangSpi<Sck, Mosi, Miso, Delay>
+where
+    Sck: OutputPin,
+    Mosi: OutputPin<Error = Sck::Error>,
+    Miso: InputPin<Error = Sck::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
+        for word in words.iter_mut() {
+            *word = self.transfer_word(*word)?;
+        }
+
+        Ok(words)
+    }
+}
+
+impl<Sck, Mosi, Miso, Delay> Write<u8> for BitBangSpi<Sck, Mosi, Miso, Delay>
+where
+    Sck: OutputPin,
+    Mosi: OutputPin<Error = Sck::Error>,
+    Miso: InputPin<Error = Sck::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        for &word in words {
+            self.transfer_word(word)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<Sck, Mosi, Miso, Delay> WriteIter<u8> for BitBangSpi<Sck, Mosi, Miso, Delay>
+where
+    Sck: OutputPin,
+    Mosi: OutputPin<Error = Sck::Error>,
+    Miso: InputPin<Error = Sck::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn write_iter<WI>(&mut self, words: WI) -> Result<(), Self::Error>
+    where
+        WI: IntoIterator<Item = u8>,
+    {
+        for word in words.into_iter() {
+            self.transfer_word(word)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<Sck, Mosi, Miso, Delay> Transactional<u8> for BitBangSpi<Sck, Mosi, Miso, Delay>
+where
+    Sck: OutputPin,
+    Mosi: OutputPin<Error = Sck::Error>,
+    Miso: InputPin<Error = Sck::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    fn exec<'a>(&mut self, operations: &mut [Operation<'a, u8>]) -> Result<(), Self::Error> {
+        for op in operations {
+            match op {
+                Operation::Write(words) => Write::write(self, words)?,
+                Operation::Transfer(words) => self.transfer(words).map(|_| ())?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<Sck, Mosi, Miso, Delay> SpiBus<u8> for BitBangSpi<Sck, Mosi, Miso, Delay>
+where
+    Sck: OutputPin,
+    Mosi: OutputPin<Error = Sck::Error>,
+    Miso: InputPin<Error = Sck::Error>,
+    Delay: DelayNs,
+    Delay::Error: core::fmt::Debug,
+{
+    /// All operations are complete when the methods of the other traits return, since words
+    /// are clocked out synchronously.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
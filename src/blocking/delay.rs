@@ -5,12 +5,119 @@
 //! The `Timer` trait provides a *non-blocking* timer abstraction and it's meant to be used to build
 //! higher level abstractions like I/O operations with timeouts. OTOH, these delays traits only
 //! provide *blocking* functionality. Note that you can also use the `timer::CountDown` trait to
-//! implement blocking delays.
+//! implement blocking delays, see [`CountDownDelay`](struct.CountDownDelay.html).
+//!
+//! # Which trait should be implemented?
+//!
+//! HALs should implement [`DelayNs`](trait.DelayNs.html). Every `DelayNs` implementation also
+//! implements `DelayMs` and `DelayUs` for `u8`, `u16` and `u32`, so that drivers depending on
+//! these traits keep working. Drivers should prefer `DelayNs` as well.
+//!
+//! Note that `DelayNs` provides `delay_ms` and `delay_us` methods too: if both `DelayNs` and
+//! `DelayMs` (or `DelayUs`) are in scope, the trait whose method is called must be
+//! disambiguated.
+
+use crate::nb::timer::CountDown;
+
+/// Nanosecond delay
+///
+/// Only `delay_ns` must be implemented. The provided `delay_us` and `delay_ms` methods split
+/// delays which would overflow a `u32` amount of nanoseconds into several `delay_ns` calls.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::delay::{DelayMs, DelayNs, DelayUs};
+/// use core::convert::Infallible;
+///
+/// /// A delay recording the requested delays
+/// struct Delay(Vec<u32>);
+///
+/// impl DelayNs for Delay {
+///     type Error = Infallible;
+///
+///     fn delay_ns(&mut self, ns: u32) -> Result<(), Infallible> {
+///         self.0.push(ns);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut delay = Delay(Vec::new());
+/// DelayNs::delay_us(&mut delay, 5_000_000).unwrap();
+/// DelayNs::delay_ms(&mut delay, 5).unwrap();
+/// assert_eq!(delay.0, [4_294_967_000, 705_033_000, 5_000_000]);
+///
+/// // drivers depending on `DelayMs` or `DelayUs` can still use it
+/// fn blink<D: DelayMs<u16> + DelayUs<u8>>(delay: &mut D) -> Result<(), Infallible> {
+///     let _ = delay.delay_ms(500_u16);
+///     let _ = delay.delay_us(10_u8);
+///     Ok(())
+/// }
+///
+/// let mut delay = Delay(Vec::new());
+/// blink(&mut delay).unwrap();
+/// assert_eq!(delay.0, [500_000_000, 10_000]);
+/// ```
+pub trait DelayNs {
+    /// Enumeration of `DelayNs` errors
+    type Error;
+
+    /// Pauses execution for at least `ns` nanoseconds
+    ///
+    /// The pause can be longer if the implementation requires it due to precision or timing
+    /// issues.
+    fn delay_ns(&mut self, ns: u32) -> Result<(), Self::Error>;
+
+    /// Pauses execution for at least `us` microseconds
+    fn delay_us(&mut self, mut us: u32) -> Result<(), Self::Error> {
+        const MAX_MICROS: u32 = u32::MAX / 1_000;
+
+        while us > MAX_MICROS {
+            self.delay_ns(MAX_MICROS * 1_000)?;
+            us -= MAX_MICROS;
+        }
+
+        self.delay_ns(us * 1_000)
+    }
+
+    /// Pauses execution for at least `ms` milliseconds
+    fn delay_ms(&mut self, mut ms: u32) -> Result<(), Self::Error> {
+        const MAX_MILLIS: u32 = u32::MAX / 1_000_000;
+
+        while ms > MAX_MILLIS {
+            self.delay_ns(MAX_MILLIS * 1_000_000)?;
+            ms -= MAX_MILLIS;
+        }
+
+        self.delay_ns(ms * 1_000_000)
+    }
+}
 
 /// Millisecond delay
 ///
 /// `UXX` denotes the range type of the delay time. `UXX` can be `u8`, `u16`, etc. A single type can
 /// implement this trait for different types of `UXX`.
+///
+/// This trait is implemented for all the [`DelayNs`](trait.DelayNs.html) implementations, for
+/// `UXX` being `u8`, `u16` or `u32`. Types which do not implement `DelayNs` can still implement
+/// this trait directly:
+///
+/// ```
+/// use embedded_hal::blocking::delay::DelayMs;
+/// use core::convert::Infallible;
+///
+/// struct Delay;
+///
+/// impl DelayMs<u16> for Delay {
+///     type Error = Infallible;
+///
+///     fn delay_ms(&mut self, ms: u16) -> Result<(), Infallible> {
+///         // ...
+/// #       let _ = ms;
+///         Ok(())
+///     }
+/// }
+/// ```
 pub trait DelayMs<UXX> {
     /// Enumeration of `DelayMs` errors
     type Error;
@@ -23,6 +130,9 @@ pub trait DelayMs<UXX> {
 ///
 /// `UXX` denotes the range type of the delay time. `UXX` can be `u8`, `u16`, etc. A single type can
 /// implement this trait for different types of `UXX`.
+///
+/// This trait is implemented for all the [`DelayNs`](trait.DelayNs.html) implementations, for
+/// `UXX` being `u8`, `u16` or `u32`.
 pub trait DelayUs<UXX> {
     /// Enumeration of `DelayMs` errors
     type Error;
@@ -30,3 +140,206 @@ pub trait DelayUs<UXX> {
     /// Pauses execution for `us` microseconds
     fn delay_us(&mut self, us: UXX) -> Result<(), Self::Error>;
 }
+
+mod sealed {
+    /// Range types of the `DelayMs` and `DelayUs` implementations of `DelayNs` delays
+    pub trait Uxx: Into<u32> {}
+
+    impl Uxx for u8 {}
+    impl Uxx for u16 {}
+    impl Uxx for u32 {}
+}
+
+impl<D, UXX> DelayMs<UXX> for D
+where
+    D: DelayNs,
+    UXX: sealed::Uxx,
+{
+    type Error = D::Error;
+
+    fn delay_ms(&mut self, ms: UXX) -> Result<(), Self::Error> {
+        DelayNs::delay_ms(self, ms.into())
+    }
+}
+
+impl<D, UXX> DelayUs<UXX> for D
+where
+    D: DelayNs,
+    UXX: sealed::Uxx,
+{
+    type Error = D::Error;
+
+    fn delay_us(&mut self, us: UXX) -> Result<(), Self::Error> {
+        DelayNs::delay_us(self, us.into())
+    }
+}
+
+/// A duration in microseconds
+///
+/// This is the unit in which [`CountDownDelay`](struct.CountDownDelay.html) starts its timer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Microseconds(pub u32);
+
+/// Blocking delays implemented on top of a [`CountDown`](../../nb/timer/trait.CountDown.html)
+/// timer
+///
+/// This type implements [`DelayNs`](trait.DelayNs.html), and thus `DelayMs` and `DelayUs`, using
+/// any timer whose `Time` can be built from [`Microseconds`](struct.Microseconds.html). Delays
+/// are rounded up to the next microsecond, and delays longer than the maximum count down of the
+/// timer are split into several count downs.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::delay::{CountDownDelay, DelayMs, DelayNs, DelayUs, Microseconds};
+/// use embedded_hal::nb::timer::{CountDown, ErrorType};
+/// use core::convert::Infallible;
+///
+/// /// A timer recording the count downs it was started with
+/// struct Timer(Vec<u32>);
+///
+/// struct Ticks(u32);
+///
+/// impl From<Microseconds> for Ticks {
+///     fn from(us: Microseconds) -> Ticks {
+///         // 1 tick per microsecond
+///         Ticks(us.0)
+///     }
+/// }
+///
+/// impl ErrorType for Timer {
+///     type Error = Infallible;
+/// }
+///
+/// impl CountDown for Timer {
+///     type Time = Ticks;
+///
+///     fn start<T: Into<Ticks>>(&mut self, count: T) -> Result<(), Infallible> {
+///         self.0.push(count.into().0);
+///         Ok(())
+///     }
+///
+///     fn wait(&mut self) -> nb::Result<(), Infallible> {
+///         Ok(())
+///     }
+/// }
+///
+/// // the timer can count down for at most 1 ms
+/// let mut delay = CountDownDelay::new(Timer(Vec::new()), 1_000);
+///
+/// delay.delay_ns(1_001).unwrap();
+/// DelayUs::delay_us(&mut delay, 250_u8).unwrap();
+/// DelayUs::delay_us(&mut delay, 2_500_u16).unwrap();
+/// DelayMs::delay_ms(&mut delay, 2_u32).unwrap();
+/// DelayMs::delay_ms(&mut delay, 0_u8).unwrap();
+///
+/// assert_eq!(delay.release().0, [2, 250, 1_000, 1_000, 500, 1_000, 1_000]);
+/// ```
+pub struct CountDownDelay<T> {
+    timer: T,
+    max_us: u32,
+}
+
+impl<T> CountDownDelay<T> {
+    /// Creates a new `CountDownDelay` from a timer
+    ///
+    /// `max_us` is the longest count down the timer supports, in microseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_us` is 0.
+    pub fn new(timer: T, max_us: u32) -> Self {
+        assert!(
+            max_us > 0,
+            "the timer must support count downs of at least 1 us"
+        );
+        Self { timer, max_us }
+    }
+
+    /// Destroys the delay and returns the timer
+    pub fn release(self) -> T {
+        self.timer
+    }
+}
+
+impl<T> CountDownDelay<T>
+where
+    T: CountDown,
+    T::Time: From<Microseconds>,
+{
+    fn delay(&mut self, count_downs: CountDowns) -> Result<(), T::Error> {
+        for count in count_downs {
+            self.timer.start(count)?;
+            nb::block!(self.timer.wait())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> DelayNs for CountDownDelay<T>
+where
+    T: CountDown,
+    T::Time: From<Microseconds>,
+{
+    type Error = T::Error;
+
+    fn delay_ns(&mut self, ns: u32) -> Result<(), Self::Error> {
+        self.delay(CountDowns::ns(ns, self.max_us))
+    }
+
+    fn delay_us(&mut self, us: u32) -> Result<(), Self::Error> {
+        self.delay(CountDowns::us(us, self.max_us))
+    }
+
+    fn delay_ms(&mut self, ms: u32) -> Result<(), Self::Error> {
+        self.delay(CountDowns::ms(ms, self.max_us))
+    }
+}
+
+/// Count downs of at most `max_us` microseconds adding up to a delay, rounded up to the next
+/// microsecond
+///
+/// Shared by the blocking and asynchronous `CountDownDelay`.
+pub(crate) struct CountDowns {
+    us: u64,
+    max_us: u32,
+}
+
+impl CountDowns {
+    pub(crate) fn ns(ns: u32, max_us: u32) -> Self {
+        let mut us = ns / 1_000;
+        if us * 1_000 < ns {
+            us += 1;
+        }
+        Self::us(us, max_us)
+    }
+
+    pub(crate) fn us(us: u32, max_us: u32) -> Self {
+        Self {
+            us: u64::from(us),
+            max_us,
+        }
+    }
+
+    pub(crate) fn ms(ms: u32, max_us: u32) -> Self {
+        Self {
+            us: u64::from(ms) * 1_000,
+            max_us,
+        }
+    }
+}
+
+impl Iterator for CountDowns {
+    type Item = Microseconds;
+
+    fn next(&mut self) -> Option<Microseconds> {
+        if self.us == 0 {
+            return None;
+        }
+
+        let count = core::cmp::min(self.us, u64::from(self.max_us)) as u32;
+        self.us -= u64::from(count);
+        Some(Microseconds(count))
+    }
+}
@@ -0,0 +1,334 @@
+//! Pin adapters
+//!
+//! These types wrap or replace pins to adapt their behaviour to the needs of a driver:
+//!
+//! - [`Inverted`](struct.Inverted.html) inverts the logic level of a pin, e.g. for active-low
+//!   LEDs or enable lines.
+//! - [`NoPin`](struct.NoPin.html) is an output pin doing nothing, for unconnected chip select or
+//!   reset lines.
+//! - [`Debounced`](struct.Debounced.html) only reports the level of an input pin once it has
+//!   been stable for a while, e.g. for push buttons.
+
+use super::{
+    Error, ErrorKind, ErrorType, InputPin, OutputPin, StatefulOutputPin, ToggleableOutputPin,
+};
+use crate::blocking::delay::DelayNs;
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+/// Pin with inverted logic levels
+///
+/// Driving an `Inverted` pin high drives the wrapped pin low and vice versa. Likewise, the state
+/// read from an `Inverted` pin is the opposite of the state of the wrapped pin.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::digital::adapters::Inverted;
+/// use embedded_hal::blocking::digital::{ErrorType, OutputPin, StatefulOutputPin};
+/// use core::convert::Infallible;
+///
+/// struct Pin(bool);
+///
+/// impl ErrorType for Pin {
+///     type Error = Infallible;
+/// }
+///
+/// impl OutputPin for Pin {
+///     fn set_low(&mut self) -> Result<(), Infallible> {
+///         self.0 = false;
+///         Ok(())
+///     }
+///
+///     fn set_high(&mut self) -> Result<(), Infallible> {
+///         self.0 = true;
+///         Ok(())
+///     }
+/// }
+///
+/// impl StatefulOutputPin for Pin {
+///     fn is_set_high(&self) -> Result<bool, Infallible> {
+///         Ok(self.0)
+///     }
+///
+///     fn is_set_low(&self) -> Result<bool, Infallible> {
+///         Ok(!self.0)
+///     }
+/// }
+///
+/// // an active-low LED
+/// let mut led = Inverted::new(Pin(true));
+/// assert!(led.is_set_low().unwrap());
+///
+/// // turn it on
+/// led.set_high().unwrap();
+/// assert!(led.is_set_high().unwrap());
+/// assert!(!led.release().0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Inverted<P> {
+    pin: P,
+}
+
+impl<P> Inverted<P> {
+    /// Creates a new `Inverted` pin wrapping `pin`
+    pub fn new(pin: P) -> Self {
+        Self { pin }
+    }
+
+    /// Destroys the adapter and returns the wrapped pin
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: ErrorType> ErrorType for Inverted<P> {
+    type Error = P::Error;
+}
+
+impl<P: OutputPin> OutputPin for Inverted<P> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_high()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_low()
+    }
+}
+
+impl<P: StatefulOutputPin> StatefulOutputPin for Inverted<P> {
+    fn is_set_high(&self) -> Result<bool, Self::Error> {
+        self.pin.is_set_low()
+    }
+
+    fn is_set_low(&self) -> Result<bool, Self::Error> {
+        self.pin.is_set_high()
+    }
+}
+
+impl<P: ToggleableOutputPin> ToggleableOutputPin for Inverted<P> {
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.pin.toggle()
+    }
+}
+
+impl<P: InputPin> InputPin for Inverted<P> {
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        self.pin.is_low()
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.pin.is_high()
+    }
+}
+
+/// Output pin which does nothing
+///
+/// Drivers requiring an output pin for a line which is not connected, e.g. the reset line of a
+/// display or the chip select line of the only device on an SPI bus, can be given a `NoPin`.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::digital::adapters::NoPin;
+/// use embedded_hal::blocking::digital::{OutputPin, ToggleableOutputPin};
+///
+/// fn reset<P: OutputPin>(reset_pin: &mut P) -> Result<(), P::Error> {
+///     reset_pin.set_low()?;
+///     reset_pin.set_high()
+/// }
+///
+/// let mut pin = NoPin::new();
+/// reset(&mut pin).unwrap();
+/// pin.toggle().unwrap();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NoPin;
+
+impl NoPin {
+    /// Creates a new `NoPin`
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ErrorType for NoPin {
+    type Error = Infallible;
+}
+
+impl OutputPin for NoPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ToggleableOutputPin for NoPin {
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Error type of [`Debounced`](struct.Debounced.html)
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DebouncedError<PIN, DELAY> {
+    /// Reading the input pin failed
+    Pin(PIN),
+    /// Waiting between two samples failed
+    Delay(DELAY),
+}
+
+impl<PIN, DELAY> Error for DebouncedError<PIN, DELAY>
+where
+    PIN: Error,
+    DELAY: core::fmt::Debug,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Pin(e) => e.kind(),
+            Self::Delay(_) => ErrorKind::Other,
+        }
+    }
+}
+
+/// Debounced input pin
+///
+/// Reading the level of a `Debounced` pin samples the wrapped input pin every `interval_us`
+/// microseconds until `stable_count` consecutive samples have the same level, which is then
+/// returned. Reading the level thus blocks for at least `(stable_count - 1) * interval_us`
+/// microseconds, and for as long as the input bounces.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::delay::DelayNs;
+/// use embedded_hal::blocking::digital::adapters::Debounced;
+/// use embedded_hal::blocking::digital::{ErrorType, InputPin};
+/// use core::cell::RefCell;
+/// use core::convert::Infallible;
+///
+/// /// A push button returning a predefined sequence of samples
+/// struct Button(RefCell<std::vec::IntoIter<bool>>);
+///
+/// impl ErrorType for Button {
+///     type Error = Infallible;
+/// }
+///
+/// impl InputPin for Button {
+///     fn is_high(&self) -> Result<bool, Infallible> {
+///         Ok(self.0.borrow_mut().next().unwrap())
+///     }
+///
+///     fn is_low(&self) -> Result<bool, Infallible> {
+///         self.is_high().map(|high| !high)
+///     }
+/// }
+///
+/// /// A delay recording the requested delays
+/// struct Delay(Vec<u32>);
+///
+/// impl DelayNs for Delay {
+///     type Error = Infallible;
+///
+///     fn delay_ns(&mut self, ns: u32) -> Result<(), Infallible> {
+///         self.0.push(ns);
+///         Ok(())
+///     }
+/// }
+///
+/// // the button bounces when pressed
+/// let samples = vec![false, true, false, true, true, true, false];
+/// let button = Button(RefCell::new(samples.into_iter()));
+/// let button = Debounced::new(button, Delay(Vec::new()), 1_000, 3);
+///
+/// assert_eq!(button.is_high(), Ok(true));
+/// let (button, delay) = button.release();
+/// assert_eq!(button.0.into_inner().len(), 1);
+/// assert_eq!(delay.0, [1_000_000; 5]);
+/// ```
+pub struct Debounced<P, D> {
+    pin: P,
+    delay: RefCell<D>,
+    interval_us: u32,
+    stable_count: u32,
+}
+
+impl<P, D> Debounced<P, D> {
+    /// Creates a new `Debounced` pin
+    ///
+    /// `pin` is sampled every `interval_us` microseconds, using `delay`, until `stable_count`
+    /// consecutive samples have the same level.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stable_count` is 0.
+    pub fn new(pin: P, delay: D, interval_us: u32, stable_count: u32) -> Self {
+        assert!(stable_count > 0, "at least one sample is required");
+        Self {
+            pin,
+            delay: RefCell::new(delay),
+            interval_us,
+            stable_count,
+        }
+    }
+
+    /// Destroys the adapter and returns the wrapped pin and delay
+    pub fn release(self) -> (P, D) {
+        (self.pin, self.delay.into_inner())
+    }
+}
+
+impl<P, D> Debounced<P, D>
+where
+    P: InputPin,
+    D: DelayNs,
+{
+    /// Samples the pin until its level is stable, returning `true` if it is high
+    fn sample(&self) -> Result<bool, DebouncedError<P::Error, D::Error>> {
+        let mut delay = self.delay.borrow_mut();
+        let mut level = self.pin.is_high().map_err(DebouncedError::Pin)?;
+        let mut count = 1;
+
+        while count < self.stable_count {
+            delay
+                .delay_us(self.interval_us)
+                .map_err(DebouncedError::Delay)?;
+            let sample = self.pin.is_high().map_err(DebouncedError::Pin)?;
+            if sample == level {
+                count += 1;
+            } else {
+                level = sample;
+                count = 1;
+            }
+        }
+
+        Ok(level)
+    }
+}
+
+impl<P, D> ErrorType for Debounced<P, D>
+where
+    P: ErrorType,
+    D: DelayNs,
+    D::Error: core::fmt::Debug,
+{
+    type Error = DebouncedError<P::Error, D::Error>;
+}
+
+impl<P, D> InputPin for Debounced<P, D>
+where
+    P: InputPin,
+    D: DelayNs,
+    D::Error: core::fmt::Debug,
+{
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        self.sample()
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.sample().map(|high| !high)
+    }
+}
@@ -0,0 +1,966 @@
+//! Digital I/O
+
+use core::{convert::From, ops::Not};
+
+pub mod adapters;
+
+/// Digital I/O error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic digital error kind
+    ///
+    /// By using this method, digital errors freely defined by HAL implementations
+    /// can be converted to a set of generic digital errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// Digital I/O error kind
+///
+/// This represents a common set of digital operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common digital errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The pin was used in a mode it is not configured for, e.g. driven while configured as an
+    /// input.
+    WrongMode,
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::WrongMode => write!(f, "The pin was used in a mode it is not configured for"),
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+/// Digital I/O error type trait
+///
+/// This just defines the error type, to be used by the other digital traits.
+pub trait ErrorType {
+    /// Error type
+    type Error: Error;
+}
+
+/// Digital output pin state
+///
+/// Conversion from `bool` and logical negation are also implemented
+/// for this type.
+/// ```rust
+/// # use embedded_hal::blocking::digital::PinState;
+/// let state = PinState::from(false);
+/// assert_eq!(state, PinState::Low);
+/// assert_eq!(!state, PinState::High);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PinState {
+    /// Low pin state
+    Low,
+    /// High pin state
+    High,
+}
+
+impl From<bool> for PinState {
+    fn from(value: bool) -> Self {
+        match value {
+            false => PinState::Low,
+            true => PinState::High,
+        }
+    }
+}
+
+impl Not for PinState {
+    type Output = PinState;
+
+    fn not(self) -> Self::Output {
+        match self {
+            PinState::High => PinState::Low,
+            PinState::Low => PinState::High,
+        }
+    }
+}
+
+/// Pull resistor configuration of an input pin
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Pull {
+    /// Internal pull-up resistor enabled
+    Up,
+    /// Internal pull-down resistor enabled
+    Down,
+    /// No internal pull resistor, the pin is floating unless driven externally
+    None,
+}
+
+/// Single digital push-pull output pin
+///
+/// See [`OpenDrainPin`](trait.OpenDrainPin.html) for pins which can only drive the line low.
+pub trait OutputPin: ErrorType {
+    /// Drives the pin low
+    ///
+    /// *NOTE* the actual electrical state of the pin may not actually be low, e.g. due to external
+    /// electrical sources
+    fn set_low(&mut self) -> Result<(), Self::Error>;
+
+    /// Drives the pin high
+    ///
+    /// *NOTE* the actual electrical state of the pin may not actually be high, e.g. due to external
+    /// electrical sources
+    fn set_high(&mut self) -> Result<(), Self::Error>;
+
+    /// Drives the pin high or low depending on the provided value
+    ///
+    /// *NOTE* the actual electrical state of the pin may not actually be high or low, e.g. due to external
+    /// electrical sources
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        match state {
+            PinState::Low => self.set_low(),
+            PinState::High => self.set_high(),
+        }
+    }
+}
+
+/// Push-pull output pin that can read its output state
+pub trait StatefulOutputPin: OutputPin {
+    /// Is the pin in drive high mode?
+    ///
+    /// *NOTE* this does *not* read the electrical state of the pin
+    fn is_set_high(&self) -> Result<bool, Self::Error>;
+
+    /// Is the pin in drive low mode?
+    ///
+    /// *NOTE* this does *not* read the electrical state of the pin
+    fn is_set_low(&self) -> Result<bool, Self::Error>;
+}
+
+/// Output pin that can be toggled
+///
+/// See [toggleable](toggleable) to use a software implementation if
+/// both [OutputPin](trait.OutputPin.html) and
+/// [StatefulOutputPin](trait.StatefulOutputPin.html) are
+/// implemented. Otherwise, implement this using hardware mechanisms.
+pub trait ToggleableOutputPin: ErrorType {
+    /// Toggle pin output.
+    fn toggle(&mut self) -> Result<(), Self::Error>;
+}
+
+/// If you can read **and** write the output state, a pin is
+/// toggleable by software.
+///
+/// ```
+/// use embedded_hal::blocking::digital::{
+///     toggleable, ErrorType, OutputPin, StatefulOutputPin, ToggleableOutputPin,
+/// };
+/// use core::convert::Infallible;
+///
+/// /// A virtual output pin that exists purely in software
+/// struct MyPin {
+///     state: bool,
+/// }
+///
+/// impl ErrorType for MyPin {
+///     type Error = Infallible;
+/// }
+///
+/// impl OutputPin for MyPin {
+///     fn set_low(&mut self) -> Result<(), Self::Error> {
+///         self.state = false;
+///         Ok(())
+///     }
+///     fn set_high(&mut self) -> Result<(), Self::Error> {
+///         self.state = true;
+///         Ok(())
+///     }
+/// }
+///
+/// impl StatefulOutputPin for MyPin {
+///     fn is_set_low(&self) -> Result<bool, Self::Error> {
+///         Ok(!self.state)
+///     }
+///     fn is_set_high(&self) -> Result<bool, Self::Error> {
+///         Ok(self.state)
+///     }
+/// }
+///
+/// /// Opt-in to the software implementation.
+/// impl toggleable::Default for MyPin {}
+///
+/// let mut pin = MyPin { state: false };
+/// pin.toggle().unwrap();
+/// assert!(pin.is_set_high().unwrap());
+/// pin.toggle().unwrap();
+/// assert!(pin.is_set_low().unwrap());
+/// ```
+///
+/// Errors reported by the underlying pin are propagated by `toggle`:
+///
+/// ```
+/// use embedded_hal::blocking::digital::{
+///     self, toggleable, ErrorKind, ErrorType, OutputPin, StatefulOutputPin,
+///     ToggleableOutputPin,
+/// };
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Error {
+///     ReadBack,
+///     Drive,
+/// }
+///
+/// impl digital::Error for Error {
+///     fn kind(&self) -> ErrorKind {
+///         ErrorKind::Other
+///     }
+/// }
+///
+/// /// A pin whose state read-back and drive operations can be made to fail
+/// struct FlakyPin {
+///     state: bool,
+///     fail_read: bool,
+///     fail_drive: bool,
+/// }
+///
+/// impl ErrorType for FlakyPin {
+///     type Error = Error;
+/// }
+///
+/// impl OutputPin for FlakyPin {
+///     fn set_low(&mut self) -> Result<(), Self::Error> {
+///         if self.fail_drive {
+///             return Err(Error::Drive);
+///         }
+///         self.state = false;
+///         Ok(())
+///     }
+///     fn set_high(&mut self) -> Result<(), Self::Error> {
+///         if self.fail_drive {
+///             return Err(Error::Drive);
+///         }
+///         self.state = true;
+///         Ok(())
+///     }
+/// }
+///
+/// impl StatefulOutputPin for FlakyPin {
+///     fn is_set_low(&self) -> Result<bool, Self::Error> {
+///         self.is_set_high().map(|high| !high)
+///     }
+///     fn is_set_high(&self) -> Result<bool, Self::Error> {
+///         if self.fail_read {
+///             return Err(Error::ReadBack);
+///         }
+///         Ok(self.state)
+///     }
+/// }
+///
+/// impl toggleable::Default for FlakyPin {}
+///
+/// let mut pin = FlakyPin { state: false, fail_read: true, fail_drive: false };
+/// assert_eq!(pin.toggle(), Err(Error::ReadBack));
+/// assert!(!pin.state);
+///
+/// pin.fail_read = false;
+/// pin.fail_drive = true;
+/// assert_eq!(pin.toggle(), Err(Error::Drive));
+/// assert!(!pin.state);
+///
+/// pin.state = true;
+/// assert_eq!(pin.toggle(), Err(Error::Drive));
+/// assert!(pin.state);
+/// ```
+pub mod toggleable {
+    use super::{OutputPin, StatefulOutputPin, ToggleableOutputPin};
+
+    /// Software-driven `toggle()` implementation.
+    pub trait Default: OutputPin + StatefulOutputPin {}
+
+    impl<P> ToggleableOutputPin for P
+    where
+        P: Default,
+    {
+        /// Toggle pin output
+        fn toggle(&mut self) -> Result<(), Self::Error> {
+            if self.is_set_high()? {
+                self.set_low()
+            } else {
+                self.set_high()
+            }
+        }
+    }
+}
+
+/// Single digital input pin
+pub trait InputPin: ErrorType {
+    /// Is the input pin high?
+    fn is_high(&self) -> Result<bool, Self::Error>;
+
+    /// Is the input pin low?
+    fn is_low(&self) -> Result<bool, Self::Error>;
+}
+
+/// Group of output pins written and read as an integer
+///
+/// Bit `i` of the masks and words passed to and returned by the methods of this trait
+/// corresponds to the `i`th pin of the port, out of `N`. Bits `N` and above are ignored, and
+/// read as 0. `N` is at most 32.
+///
+/// Implementations should write all the pins at once, e.g. using a port register, so that
+/// parallel buses are driven without glitches. See [`PinPort`](struct.PinPort.html) for a
+/// software implementation which writes the pins one by one.
+pub trait OutputPort<const N: usize>: ErrorType {
+    /// Drives the pins selected by `mask` to the state of the corresponding bits of `word`
+    ///
+    /// Pins not selected by `mask` keep their state.
+    fn write(&mut self, mask: u32, word: u32) -> Result<(), Self::Error>;
+
+    /// Reads the output state of the pins selected by `mask`
+    ///
+    /// Bits not selected by `mask` are read as 0.
+    ///
+    /// *NOTE* this does *not* read the electrical state of the pins
+    fn read(&self, mask: u32) -> Result<u32, Self::Error>;
+
+    /// Drives all the pins to the state of the corresponding bits of `word`
+    fn write_all(&mut self, word: u32) -> Result<(), Self::Error> {
+        self.write(port_mask(N), word)
+    }
+}
+
+/// Returns the mask selecting all the pins of a port of `n` pins
+fn port_mask(n: usize) -> u32 {
+    if n >= 32 {
+        u32::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Software implementation of `OutputPort` on top of `N` [`OutputPin`](trait.OutputPin.html)s
+///
+/// *NOTE* the pins are written one by one, starting with pin 0: the writes are *not* atomic and
+/// the pins briefly go through intermediate states. Only use this type with devices which latch
+/// the port state on a separate strobe signal, or which tolerate glitches.
+///
+/// The output state read with `read` is the last state written by this type. Pins which were not
+/// written yet are read as low.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::digital::{ErrorType, OutputPin, OutputPort, PinPort};
+/// use core::cell::RefCell;
+/// use core::convert::Infallible;
+///
+/// /// An output pin logging its writes as `(pin number, state)`
+/// struct Pin<'a> {
+///     number: u8,
+///     log: &'a RefCell<Vec<(u8, bool)>>,
+/// }
+///
+/// impl ErrorType for Pin<'_> {
+///     type Error = Infallible;
+/// }
+///
+/// impl OutputPin for Pin<'_> {
+///     fn set_low(&mut self) -> Result<(), Infallible> {
+///         self.log.borrow_mut().push((self.number, false));
+///         Ok(())
+///     }
+///
+///     fn set_high(&mut self) -> Result<(), Infallible> {
+///         self.log.borrow_mut().push((self.number, true));
+///         Ok(())
+///     }
+/// }
+///
+/// let log = RefCell::new(Vec::new());
+/// let pin = |number| Pin { number, log: &log };
+/// let mut port = PinPort::new([pin(0), pin(1), pin(2), pin(3)]);
+///
+/// port.write_all(0b0101).unwrap();
+/// port.write(0b0110, 0b1111_0010).unwrap();
+/// assert_eq!(port.read(0b1111).unwrap(), 0b0011);
+/// assert_eq!(port.read(0b1000_0001).unwrap(), 0b0001);
+/// assert_eq!(
+///     log.into_inner(),
+///     [(0, true), (1, false), (2, true), (3, false), (1, true), (2, false)]
+/// );
+/// ```
+pub struct PinPort<P, const N: usize> {
+    pins: [P; N],
+    state: u32,
+}
+
+impl<P, const N: usize> PinPort<P, N> {
+    /// Fails to evaluate, and thus to compile, if the port has more than 32 pins
+    const PIN_COUNT_CHECK: usize = 32 - N;
+
+    /// Creates a new `PinPort` from its pins, pin 0 first
+    ///
+    /// A port has at most 32 pins: creating a port of more pins fails to compile.
+    ///
+    /// ``` compile_fail
+    /// use embedded_hal::blocking::digital::PinPort;
+    ///
+    /// let port = PinPort::new([(); 33]);
+    /// ```
+    pub fn new(pins: [P; N]) -> Self {
+        let _ = Self::PIN_COUNT_CHECK;
+        Self { pins, state: 0 }
+    }
+
+    /// Destroys the port and returns its pins
+    pub fn release(self) -> [P; N] {
+        self.pins
+    }
+}
+
+impl<P: ErrorType, const N: usize> ErrorType for PinPort<P, N> {
+    type Error = P::Error;
+}
+
+impl<P: OutputPin, const N: usize> OutputPort<N> for PinPort<P, N> {
+    fn write(&mut self, mask: u32, word: u32) -> Result<(), Self::Error> {
+        for (i, pin) in self.pins.iter_mut().enumerate() {
+            let bit = 1 << i;
+            if mask & bit == 0 {
+                continue;
+            }
+
+            pin.set_state(PinState::from(word & bit != 0))?;
+            self.state = (self.state & !bit) | (word & bit);
+        }
+
+        Ok(())
+    }
+
+    fn read(&self, mask: u32) -> Result<u32, Self::Error> {
+        Ok(self.state & mask)
+    }
+}
+
+/// Single digital open-drain pin
+///
+/// An open-drain pin either drives the line low or releases it, letting a pull-up resistor (or
+/// another device) set the line level. This is the electrical behaviour required by wired-AND
+/// buses like I2C and 1-Wire and by shared interrupt lines.
+///
+/// The `InputPin` methods read back the actual level of the line, which is low whenever any
+/// device on the line drives it low.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::digital::{ErrorType, InputPin, OpenDrainPin};
+/// use core::cell::Cell;
+/// use core::convert::Infallible;
+/// use std::rc::Rc;
+///
+/// /// A line with a pull-up resistor, stored as a bitmask of the devices driving it low
+/// type Line = Rc<Cell<u8>>;
+///
+/// struct Pin {
+///     line: Line,
+///     mask: u8,
+/// }
+///
+/// impl ErrorType for Pin {
+///     type Error = Infallible;
+/// }
+///
+/// impl InputPin for Pin {
+///     fn is_high(&self) -> Result<bool, Infallible> {
+///         Ok(self.line.get() == 0)
+///     }
+///
+///     fn is_low(&self) -> Result<bool, Infallible> {
+///         self.is_high().map(|high| !high)
+///     }
+/// }
+///
+/// impl OpenDrainPin for Pin {
+///     fn release(&mut self) -> Result<(), Infallible> {
+///         self.line.set(self.line.get() & !self.mask);
+///         Ok(())
+///     }
+///
+///     fn drive_low(&mut self) -> Result<(), Infallible> {
+///         self.line.set(self.line.get() | self.mask);
+///         Ok(())
+///     }
+/// }
+///
+/// let line = Line::default();
+/// let mut a = Pin { line: line.clone(), mask: 1 };
+/// let mut b = Pin { line, mask: 2 };
+///
+/// assert!(a.is_high().unwrap());
+/// b.drive_low().unwrap();
+/// // `a` reads back the level set by `b`
+/// a.release().unwrap();
+/// assert!(a.is_low().unwrap());
+/// b.release().unwrap();
+/// assert!(a.is_high().unwrap());
+/// ```
+pub trait OpenDrainPin: InputPin {
+    /// Releases the line, i.e. stops driving it
+    ///
+    /// *NOTE* the line stays low if another device drives it low
+    fn release(&mut self) -> Result<(), Self::Error>;
+
+    /// Drives the line low
+    fn drive_low(&mut self) -> Result<(), Self::Error>;
+
+    /// Releases the line if `state` is `High`, drives it low otherwise
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        match state {
+            PinState::Low => self.drive_low(),
+            PinState::High => self.release(),
+        }
+    }
+}
+
+/// Input pin whose internal pull resistor can be configured
+pub trait PullConfig: ErrorType {
+    /// Configures the internal pull resistor of the pin
+    fn set_pull(&mut self, pull: Pull) -> Result<(), Self::Error>;
+}
+
+/// Wait for the state of an input pin (blocking variant)
+///
+/// This allows drivers to wait for data ready or interrupt lines without busy-polling `InputPin`
+/// themselves. HALs should implement it using their external interrupt support, sleeping until
+/// the awaited level or edge is detected. HALs without such support can use
+/// [`PollingWait`](struct.PollingWait.html).
+///
+/// Only edges happening after a method is called are detected.
+pub trait Wait: ErrorType {
+    /// Blocks until the pin is high
+    ///
+    /// Returns immediately if the pin is already high.
+    fn wait_for_high(&mut self) -> Result<(), Self::Error>;
+
+    /// Blocks until the pin is low
+    ///
+    /// Returns immediately if the pin is already low.
+    fn wait_for_low(&mut self) -> Result<(), Self::Error>;
+
+    /// Blocks until the pin undergoes a transition from low to high
+    fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error>;
+
+    /// Blocks until the pin undergoes a transition from high to low
+    fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error>;
+
+    /// Blocks until the pin undergoes any transition, i.e low to high OR high to low
+    fn wait_for_any_edge(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Software implementation of the `Wait` traits on top of an [`InputPin`](trait.InputPin.html)
+///
+/// This type implements `Wait` as well as [`nb::digital::Wait`] (and `asynch::digital::Wait`
+/// if the `"async"` feature is enabled) by repeatedly sampling the input pin. Edges are detected
+/// by comparing consecutive samples, so pulses shorter than the polling interval can be missed.
+///
+/// [`nb::digital::Wait`]: ../../nb/digital/trait.Wait.html
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::digital::{ErrorType, InputPin, PollingWait, Wait};
+/// use core::cell::RefCell;
+/// use core::convert::Infallible;
+///
+/// /// An input pin returning a predefined sequence of samples
+/// struct Pin(RefCell<std::vec::IntoIter<bool>>);
+///
+/// impl ErrorType for Pin {
+///     type Error = Infallible;
+/// }
+///
+/// impl InputPin for Pin {
+///     fn is_high(&self) -> Result<bool, Infallible> {
+///         Ok(self.0.borrow_mut().next().unwrap())
+///     }
+///
+///     fn is_low(&self) -> Result<bool, Infallible> {
+///         self.is_high().map(|high| !high)
+///     }
+/// }
+///
+/// let samples = vec![true, true, false, false, true, false, true, true];
+/// let mut pin = PollingWait::new(Pin(RefCell::new(samples.into_iter())));
+///
+/// // already high
+/// pin.wait_for_high().unwrap();
+/// // the pin being high, `wait_for_rising_edge` has to wait for it to go low first
+/// pin.wait_for_rising_edge().unwrap();
+/// pin.wait_for_any_edge().unwrap();
+/// pin.wait_for_high().unwrap();
+/// assert_eq!(pin.release().0.into_inner().len(), 0);
+/// ```
+pub struct PollingWait<P> {
+    pin: P,
+    previous: Option<bool>,
+}
+
+impl<P> PollingWait<P> {
+    /// Creates a new `PollingWait` sampling `pin`
+    pub fn new(pin: P) -> Self {
+        Self {
+            pin,
+            previous: None,
+        }
+    }
+
+    /// Destroys the adapter and returns the input pin
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: InputPin> PollingWait<P> {
+    /// Forgets the previous sample, so that edge detection starts over
+    pub(crate) fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Samples the pin once, completing if it is at the `high` level
+    pub(crate) fn poll_level(&mut self, high: bool) -> nb::Result<(), P::Error> {
+        self.reset();
+        if self.pin.is_high()? == high {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Samples the pin once, completing if a rising and/or falling edge happened since the
+    /// previous sample
+    pub(crate) fn poll_edge(&mut self, rising: bool, falling: bool) -> nb::Result<(), P::Error> {
+        let high = self.pin.is_high()?;
+        match self.previous.replace(high) {
+            Some(false) if high && rising => {}
+            Some(true) if !high && falling => {}
+            _ => return Err(nb::Error::WouldBlock),
+        }
+
+        self.reset();
+        Ok(())
+    }
+}
+
+impl<P: ErrorType> ErrorType for PollingWait<P> {
+    type Error = P::Error;
+}
+
+impl<P: InputPin> InputPin for PollingWait<P> {
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        self.pin.is_high()
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.pin.is_low()
+    }
+}
+
+impl<P: InputPin> Wait for PollingWait<P> {
+    fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        nb::block!(self.poll_level(true))
+    }
+
+    fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        nb::block!(self.poll_level(false))
+    }
+
+    fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.reset();
+        nb::block!(self.poll_edge(true, false))
+    }
+
+    fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.reset();
+        nb::block!(self.poll_edge(false, true))
+    }
+
+    fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.reset();
+        nb::block!(self.poll_edge(true, true))
+    }
+}
+
+/// Single pin that can switch from input to output mode, and vice-versa.
+///
+/// Example use (assumes the `Error` type is the same for the `IoPin`,
+/// `InputPin`, and `OutputPin`):
+///
+/// ```
+/// use core::time::Duration;
+/// use embedded_hal::blocking::digital::{IoPin, InputPin, OutputPin};
+///
+/// pub fn ping_and_read<TInputPin, TOutputPin, TError>(
+///     mut pin: TOutputPin, delay_fn: &dyn Fn(Duration) -> ()) -> Result<bool, TError>
+/// where
+///     TInputPin : InputPin<Error = TError> + IoPin<TInputPin, TOutputPin, Error = TError>,
+///     TOutputPin : OutputPin<Error = TError> + IoPin<TInputPin, TOutputPin, Error = TError>,
+/// {
+///     // Ping
+///     pin.set_low()?;
+///     delay_fn(Duration::from_millis(10));
+///     pin.set_high()?;
+///
+///     // Read
+///     let pin = pin.into_input_pin()?;
+///     delay_fn(Duration::from_millis(10));
+///     pin.is_high()
+/// }
+/// ```
+pub trait IoPin<TInput, TOutput>: ErrorType
+where
+    TInput: InputPin + IoPin<TInput, TOutput>,
+    TOutput: OutputPin + IoPin<TInput, TOutput>,
+{
+    /// Tries to convert this pin to input mode.
+    ///
+    /// If the pin is already in input mode, this method should succeed.
+    fn into_input_pin(self) -> Result<TInput, Self::Error>;
+
+    /// Tries to convert this pin to input mode with the given pull resistor configuration.
+    ///
+    /// The default implementation switches to input mode then configures the pull resistor.
+    /// Implementations should override it if the pin can be switched to input mode with the
+    /// pull resistor already configured.
+    ///
+    /// ```
+    /// use embedded_hal::blocking::digital::{
+    ///     ErrorType, InputPin, IoPin, OutputPin, PinState, Pull, PullConfig,
+    /// };
+    /// use core::convert::Infallible;
+    ///
+    /// struct Input(Pull);
+    /// struct Output(PinState);
+    ///
+    /// impl ErrorType for Input {
+    ///     type Error = Infallible;
+    /// }
+    ///
+    /// impl ErrorType for Output {
+    ///     type Error = Infallible;
+    /// }
+    ///
+    /// impl InputPin for Input {
+    ///     fn is_high(&self) -> Result<bool, Infallible> {
+    ///         // an unconnected pin reads its pull level
+    ///         Ok(self.0 == Pull::Up)
+    ///     }
+    ///
+    ///     fn is_low(&self) -> Result<bool, Infallible> {
+    ///         self.is_high().map(|high| !high)
+    ///     }
+    /// }
+    ///
+    /// impl PullConfig for Input {
+    ///     fn set_pull(&mut self, pull: Pull) -> Result<(), Infallible> {
+    ///         self.0 = pull;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// impl OutputPin for Output {
+    ///     fn set_low(&mut self) -> Result<(), Infallible> {
+    ///         self.0 = PinState::Low;
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn set_high(&mut self) -> Result<(), Infallible> {
+    ///         self.0 = PinState::High;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// impl IoPin<Input, Output> for Input {
+    ///     fn into_input_pin(self) -> Result<Input, Infallible> {
+    ///         Ok(self)
+    ///     }
+    ///
+    ///     fn into_output_pin(self, state: PinState) -> Result<Output, Infallible> {
+    ///         Ok(Output(state))
+    ///     }
+    /// }
+    ///
+    /// impl IoPin<Input, Output> for Output {
+    ///     fn into_input_pin(self) -> Result<Input, Infallible> {
+    ///         Ok(Input(Pull::None))
+    ///     }
+    ///
+    ///     fn into_output_pin(self, state: PinState) -> Result<Output, Infallible> {
+    ///         Ok(Output(state))
+    ///     }
+    /// }
+    ///
+    /// let pin = Output(PinState::Low);
+    /// let pin = pin.into_input_pin_with_pull(Pull::Up).unwrap();
+    /// assert!(pin.is_high().unwrap());
+    /// let pin = pin.into_input_pin_with_pull(Pull::Down).unwrap();
+    /// assert!(pin.is_low().unwrap());
+    /// ```
+    fn into_input_pin_with_pull(self, pull: Pull) -> Result<TInput, Self::Error>
+    where
+        Self: Sized,
+        TInput: PullConfig<Error = Self::Error>,
+    {
+        let mut pin = self.into_input_pin()?;
+        pin.set_pull(pull)?;
+        Ok(pin)
+    }
+
+    /// Tries to convert this pin to output mode with the given initial state.
+    ///
+    /// If the pin is already in the requested state, this method should
+    /// succeed.
+    fn into_output_pin(self, state: PinState) -> Result<TOutput, Self::Error>;
+}
+
+/// Mode of a [`FlexPin`](trait.FlexPin.html)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PinMode {
+    /// The pin is configured as an input
+    Input,
+    /// The pin is configured as an output
+    Output,
+}
+
+/// Single pin whose mode can be changed at runtime
+///
+/// Unlike [`IoPin`](trait.IoPin.html), switching between input and output modes does not
+/// consume the pin nor change its type, so that the pin can be stored in a driver.
+///
+/// A `FlexPin` implements both `InputPin` and `OutputPin`, but only the methods of the current
+/// mode may be used: calling the `OutputPin` methods in input mode, or the `InputPin` methods in
+/// output mode, must fail with an error of kind [`ErrorKind::WrongMode`], without affecting the
+/// pin.
+///
+/// [`ErrorKind::WrongMode`]: enum.ErrorKind.html#variant.WrongMode
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::digital::{
+///     ErrorKind, ErrorType, FlexPin, InputPin, OutputPin, PinMode, PinState,
+/// };
+///
+/// /// A pin connected to a device pulling the line low whenever the pin is an input
+/// struct Pin {
+///     mode: PinMode,
+///     state: PinState,
+/// }
+///
+/// impl ErrorType for Pin {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl InputPin for Pin {
+///     fn is_high(&self) -> Result<bool, ErrorKind> {
+///         match self.mode {
+///             PinMode::Input => Ok(false),
+///             PinMode::Output => Err(ErrorKind::WrongMode),
+///         }
+///     }
+///
+///     fn is_low(&self) -> Result<bool, ErrorKind> {
+///         self.is_high().map(|high| !high)
+///     }
+/// }
+///
+/// impl OutputPin for Pin {
+///     fn set_low(&mut self) -> Result<(), ErrorKind> {
+///         self.set_state(PinState::Low)
+///     }
+///
+///     fn set_high(&mut self) -> Result<(), ErrorKind> {
+///         self.set_state(PinState::High)
+///     }
+///
+///     fn set_state(&mut self, state: PinState) -> Result<(), ErrorKind> {
+///         match self.mode {
+///             PinMode::Input => Err(ErrorKind::WrongMode),
+///             PinMode::Output => {
+///                 self.state = state;
+///                 Ok(())
+///             }
+///         }
+///     }
+/// }
+///
+/// impl FlexPin for Pin {
+///     fn set_as_input(&mut self) -> Result<(), ErrorKind> {
+///         self.mode = PinMode::Input;
+///         Ok(())
+///     }
+///
+///     fn set_as_output(&mut self, state: PinState) -> Result<(), ErrorKind> {
+///         self.mode = PinMode::Output;
+///         self.state = state;
+///         Ok(())
+///     }
+///
+///     fn mode(&self) -> PinMode {
+///         self.mode
+///     }
+/// }
+///
+/// /// A driver owning its data line
+/// struct Driver<P> {
+///     pin: P,
+/// }
+///
+/// impl<P: FlexPin> Driver<P> {
+///     /// Sends a start signal and checks that the device answers
+///     fn start(&mut self) -> Result<bool, P::Error> {
+///         self.pin.set_as_output(PinState::High)?;
+///         self.pin.set_low()?;
+///         self.pin.set_high()?;
+///         self.pin.set_as_input()?;
+///         self.pin.is_low()
+///     }
+/// }
+///
+/// let mut driver = Driver { pin: Pin { mode: PinMode::Input, state: PinState::Low } };
+/// assert_eq!(driver.start(), Ok(true));
+/// assert_eq!(driver.pin.mode(), PinMode::Input);
+///
+/// // using the pin in the wrong mode fails
+/// assert_eq!(driver.pin.set_high(), Err(ErrorKind::WrongMode));
+/// driver.pin.set_as_output(PinState::Low).unwrap();
+/// assert_eq!(driver.pin.is_high(), Err(ErrorKind::WrongMode));
+/// ```
+pub trait FlexPin: InputPin + OutputPin {
+    /// Configures the pin as an input
+    ///
+    /// If the pin is already an input, this method should succeed.
+    fn set_as_input(&mut self) -> Result<(), Self::Error>;
+
+    /// Configures the pin as an output driven to `state`
+    ///
+    /// If the pin is already an output, this method should succeed and drive the pin to `state`.
+    fn set_as_output(&mut self, state: PinState) -> Result<(), Self::Error>;
+
+    /// Returns the current mode of the pin
+    fn mode(&self) -> PinMode;
+}
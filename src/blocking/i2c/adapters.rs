@@ -0,0 +1,497 @@
+//! I2C adapters
+//!
+//! These types wrap an I2C bus to provide I2C traits it does not implement:
+//!
+//! - [`TenBitAddressing`](struct.TenBitAddressing.html) emulates 10-bit addressing on top of a
+//!   bus only supporting 7-bit addresses.
+//! - [`TransactionalI2c`](struct.TransactionalI2c.html) implements the other blocking I2C traits
+//!   on top of `Transactional`.
+
+use super::{
+    AddressMode, ErrorType, Operation, Read, SevenBitAddress, TenBitAddress, Transactional,
+    TransactionalIter, Write, WriteIter, WriteIterRead, WriteRead,
+};
+use core::iter::once;
+
+/// Software-emulated 10-bit addressing
+///
+/// A 10-bit address is sent as two bytes: a header made of the reserved 7-bit address `11110xx`,
+/// where `xx` are the 2 most significant bits of the address, followed by the read/write bit,
+/// then the 8 least significant bits of the address. Reads start by writing both bytes, then
+/// send a repeated start condition and the header alone with the read bit set:
+///
+/// ``` text
+/// Write: ST 11110xx+W A7..A0 B0 ... BN SP
+/// Read:  ST 11110xx+W A7..A0 SR 11110xx+R B0 ... BN SP
+/// ```
+///
+/// This type implements `Read`, `Write` and `WriteRead` with 10-bit addresses for any bus
+/// implementing [`Transactional`](../trait.Transactional.html) with 7-bit addresses. The
+/// operations of `Transactional` and `TransactionalIter` transactions are passed lazily to the
+/// wrapped bus, so it must implement [`TransactionalIter`](../trait.TransactionalIter.html) with
+/// 7-bit addresses. In both cases the 8 least significant bits of the address are written at the
+/// start of the transaction and after each repeated start followed by a write.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::i2c::adapters::TenBitAddressing;
+/// use embedded_hal::blocking::i2c::{
+///     ErrorType, Operation, Read, Transactional, TransactionalIter, Write, WriteRead,
+/// };
+///
+/// # use embedded_hal::blocking::i2c::AddressMode;
+/// # use core::convert::Infallible;
+/// # use core::fmt::UpperHex;
+/// # /// A bus logging the events of its transactions, following the transaction contract
+/// # struct Bus(Vec<String>);
+/// # impl ErrorType for Bus {
+/// #     type Error = Infallible;
+/// # }
+/// # impl<A: AddressMode + UpperHex> TransactionalIter<A> for Bus {
+/// #     fn exec_iter<'a, O>(&mut self, address: A, operations: O) -> Result<(), Infallible>
+/// #     where
+/// #         O: IntoIterator<Item = Operation<'a>>,
+/// #     {
+/// #         let mut events: Vec<String> = Vec::new();
+/// #         let mut reading = None;
+/// #         for operation in operations {
+/// #             let read = matches!(operation, Operation::Read(_));
+/// #             if reading != Some(read) {
+/// #                 let start = if reading.is_none() { "ST" } else { "SR" };
+/// #                 let direction = if read { "R" } else { "W" };
+/// #                 events.push(format!("{} {:#X}+{}", start, address, direction));
+/// #                 reading = Some(read);
+/// #             }
+/// #             match operation {
+/// #                 Operation::Write(bytes) => {
+/// #                     events.extend(bytes.iter().map(|b| format!("{:#04X}", b)))
+/// #                 }
+/// #                 Operation::Read(buffer) => {
+/// #                     for (i, byte) in buffer.iter_mut().enumerate() {
+/// #                         *byte = i as u8;
+/// #                         // the master acknowledges every byte but the last one
+/// #                         if events.last().map(String::as_str) == Some("NMAK") {
+/// #                             events.pop();
+/// #                             events.push("MAK".to_string());
+/// #                         }
+/// #                         events.push("I".to_string());
+/// #                         events.push("NMAK".to_string());
+/// #                     }
+/// #                 }
+/// #             }
+/// #         }
+/// #         events.push("SP".to_string());
+/// #         self.0.push(events.join(" "));
+/// #         Ok(())
+/// #     }
+/// # }
+/// # impl<A: AddressMode + UpperHex> Transactional<A> for Bus {
+/// #     fn exec<'a>(&mut self, address: A, operations: &mut [Operation<'a>]) -> Result<(), Infallible> {
+/// #         let operations = operations.iter_mut().map(|operation| match operation {
+/// #             Operation::Read(buffer) => Operation::Read(buffer),
+/// #             Operation::Write(bytes) => Operation::Write(bytes),
+/// #         });
+/// #         self.exec_iter(address, operations)
+/// #     }
+/// # }
+/// // `Bus` is a 7-bit bus logging the I2C events of its transactions
+/// let mut i2c = TenBitAddressing::new(Bus(Vec::new()));
+/// let mut buffer = [0; 2];
+///
+/// i2c.write(0x2C5_u16, &[0x01, 0x02]).unwrap();
+/// i2c.read(0x2C5_u16, &mut buffer).unwrap();
+/// i2c.write_read(0x2C5_u16, &[0x01], &mut buffer).unwrap();
+/// i2c.exec(
+///     0x2C5_u16,
+///     &mut [
+///         Operation::Read(&mut [0]),
+///         Operation::Write(&[0x03]),
+///     ],
+/// )
+/// .unwrap();
+///
+/// assert_eq!(buffer, [0, 1]);
+/// assert_eq!(
+///     i2c.release().0,
+///     [
+///         "ST 0x7A+W 0xC5 0x01 0x02 SP",
+///         "ST 0x7A+W 0xC5 SR 0x7A+R I MAK I NMAK SP",
+///         "ST 0x7A+W 0xC5 0x01 SR 0x7A+R I MAK I NMAK SP",
+///         "ST 0x7A+W 0xC5 SR 0x7A+R I NMAK SR 0x7A+W 0xC5 0x03 SP",
+///     ]
+/// );
+/// ```
+pub struct TenBitAddressing<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C> TenBitAddressing<I2C> {
+    /// Creates a new `TenBitAddressing` wrapping a 7-bit I2C bus
+    pub fn new(i2c: I2C) -> Self {
+        Self { i2c }
+    }
+
+    /// Destroys the adapter and returns the wrapped I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+/// Splits a 10-bit address into the 7-bit header address and the 8 least significant bits
+fn split(address: TenBitAddress) -> (SevenBitAddress, [u8; 1]) {
+    let header = 0b111_1000 | ((address >> 8) as u8 & 0b11);
+    (header, [address as u8])
+}
+
+impl<I2C: ErrorType> ErrorType for TenBitAddressing<I2C> {
+    type Error = I2C::Error;
+}
+
+impl<I2C> Read<TenBitAddress> for TenBitAddressing<I2C>
+where
+    I2C: Transactional<SevenBitAddress>,
+{
+    fn read(&mut self, address: TenBitAddress, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let (header, low) = split(address);
+        self.i2c.exec(
+            header,
+            &mut [Operation::Write(&low), Operation::Read(buffer)],
+        )
+    }
+}
+
+impl<I2C> Write<TenBitAddress> for TenBitAddressing<I2C>
+where
+    I2C: Transactional<SevenBitAddress>,
+{
+    fn write(&mut self, address: TenBitAddress, bytes: &[u8]) -> Result<(), Self::Error> {
+        let (header, low) = split(address);
+        self.i2c.exec(
+            header,
+            &mut [Operation::Write(&low), Operation::Write(bytes)],
+        )
+    }
+}
+
+impl<I2C> WriteRead<TenBitAddress> for TenBitAddressing<I2C>
+where
+    I2C: Transactional<SevenBitAddress>,
+{
+    fn write_read(
+        &mut self,
+        address: TenBitAddress,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let (header, low) = split(address);
+        self.i2c.exec(
+            header,
+            &mut [
+                Operation::Write(&low),
+                Operation::Write(bytes),
+                Operation::Read(buffer),
+            ],
+        )
+    }
+}
+
+impl<I2C> Transactional<TenBitAddress> for TenBitAddressing<I2C>
+where
+    I2C: TransactionalIter<SevenBitAddress>,
+{
+    fn exec<'a>(
+        &mut self,
+        address: TenBitAddress,
+        operations: &mut [Operation<'a>],
+    ) -> Result<(), Self::Error> {
+        let operations = operations.iter_mut().map(|operation| match operation {
+            Operation::Read(buffer) => Operation::Read(buffer),
+            Operation::Write(bytes) => Operation::Write(bytes),
+        });
+        self.exec_iter(address, operations)
+    }
+}
+
+impl<I2C> TransactionalIter<TenBitAddress> for TenBitAddressing<I2C>
+where
+    I2C: TransactionalIter<SevenBitAddress>,
+{
+    fn exec_iter<'a, O>(&mut self, address: TenBitAddress, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Operation<'a>>,
+    {
+        let (header, low) = split(address);
+
+        // the address is written at the start and after each switch from reading to writing
+        let mut reading = false;
+        let operations = operations.into_iter().flat_map(|operation| {
+            let write = matches!(operation, Operation::Write(_));
+            let address = if reading && write {
+                Some(Operation::Write(&low))
+            } else {
+                None
+            };
+            reading = !write;
+            address.into_iter().chain(once(operation))
+        });
+        self.i2c
+            .exec_iter(header, once(Operation::Write(&low)).chain(operations))
+    }
+}
+
+/// Blocking I2C traits implemented on top of [`Transactional`](../trait.Transactional.html)
+///
+/// This type implements `Read`, `Write` and `WriteRead` for any `Transactional` implementation,
+/// for each address mode the wrapped bus supports. Each call is mapped to a single call to
+/// `exec`, whose transaction contract results in the I2C events documented by these traits:
+///
+/// - `read` executes `[Operation::Read(buffer)]`,
+/// - `write` executes `[Operation::Write(bytes)]`,
+/// - `write_read` executes `[Operation::Write(bytes), Operation::Read(buffer)]`.
+///
+/// The bytes of an iterator cannot be passed to `exec` without allocating, so `WriteIter` and
+/// `WriteIterRead` are implemented if the wrapped bus also implements
+/// [`TransactionalIter`](../trait.TransactionalIter.html): each byte is passed to `exec_iter` as
+/// a 1-byte `Operation::Write`, and the transaction contract sends adjacent writes without SR in
+/// between.
+///
+/// `Transactional` and `TransactionalIter` are passed through to the wrapped bus.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::i2c::adapters::TransactionalI2c;
+/// use embedded_hal::blocking::i2c::{
+///     ErrorType, Operation, Read, Transactional, TransactionalIter, Write, WriteIter,
+///     WriteIterRead, WriteRead,
+/// };
+///
+/// # use embedded_hal::blocking::i2c::AddressMode;
+/// # use core::convert::Infallible;
+/// # use core::fmt::UpperHex;
+/// # /// A bus logging the events of its transactions, following the transaction contract
+/// # struct Bus(Vec<String>);
+/// # impl ErrorType for Bus {
+/// #     type Error = Infallible;
+/// # }
+/// # impl<A: AddressMode + UpperHex> TransactionalIter<A> for Bus {
+/// #     fn exec_iter<'a, O>(&mut self, address: A, operations: O) -> Result<(), Infallible>
+/// #     where
+/// #         O: IntoIterator<Item = Operation<'a>>,
+/// #     {
+/// #         let mut events: Vec<String> = Vec::new();
+/// #         let mut reading = None;
+/// #         for operation in operations {
+/// #             let read = matches!(operation, Operation::Read(_));
+/// #             if reading != Some(read) {
+/// #                 let start = if reading.is_none() { "ST" } else { "SR" };
+/// #                 let direction = if read { "R" } else { "W" };
+/// #                 events.push(format!("{} {:#X}+{}", start, address, direction));
+/// #                 reading = Some(read);
+/// #             }
+/// #             match operation {
+/// #                 Operation::Write(bytes) => {
+/// #                     events.extend(bytes.iter().map(|b| format!("{:#04X}", b)))
+/// #                 }
+/// #                 Operation::Read(buffer) => {
+/// #                     for (i, byte) in buffer.iter_mut().enumerate() {
+/// #                         *byte = i as u8;
+/// #                         // the master acknowledges every byte but the last one
+/// #                         if events.last().map(String::as_str) == Some("NMAK") {
+/// #                             events.pop();
+/// #                             events.push("MAK".to_string());
+/// #                         }
+/// #                         events.push("I".to_string());
+/// #                         events.push("NMAK".to_string());
+/// #                     }
+/// #                 }
+/// #             }
+/// #         }
+/// #         events.push("SP".to_string());
+/// #         self.0.push(events.join(" "));
+/// #         Ok(())
+/// #     }
+/// # }
+/// # impl<A: AddressMode + UpperHex> Transactional<A> for Bus {
+/// #     fn exec<'a>(&mut self, address: A, operations: &mut [Operation<'a>]) -> Result<(), Infallible> {
+/// #         let operations = operations.iter_mut().map(|operation| match operation {
+/// #             Operation::Read(buffer) => Operation::Read(buffer),
+/// #             Operation::Write(bytes) => Operation::Write(bytes),
+/// #         });
+/// #         self.exec_iter(address, operations)
+/// #     }
+/// # }
+/// // `Bus` logs the I2C events of its transactions
+/// let mut i2c = TransactionalI2c::new(Bus(Vec::new()));
+/// let mut buffer = [0xFF; 2];
+///
+/// i2c.write(0x50_u8, &[0x01, 0x02]).unwrap();
+/// i2c.read(0x50_u8, &mut buffer).unwrap();
+/// assert_eq!(buffer, [0, 1]);
+/// i2c.write_read(0x50_u8, &[0x01], &mut buffer[..1]).unwrap();
+/// i2c.write_iter(0x50_u8, 1..=3).unwrap();
+/// i2c.write_iter_read(0x2C5_u16, vec![0x01], &mut buffer).unwrap();
+///
+/// // the device is addressed even without bytes to write
+/// i2c.write_iter(0x50_u8, None).unwrap();
+/// i2c.write_iter_read(0x50_u8, None, &mut buffer).unwrap();
+///
+/// // back-to-back reads are sent without SR in between
+/// let (mut first, mut second) = ([0xFF; 2], [0xFF; 1]);
+/// i2c.exec(0x50_u8, &mut [Operation::Read(&mut first), Operation::Read(&mut second)])
+///     .unwrap();
+///
+/// assert_eq!(
+///     i2c.release().0,
+///     [
+///         "ST 0x50+W 0x01 0x02 SP",
+///         "ST 0x50+R I MAK I NMAK SP",
+///         "ST 0x50+W 0x01 SR 0x50+R I NMAK SP",
+///         "ST 0x50+W 0x01 0x02 0x03 SP",
+///         "ST 0x2C5+W 0x01 SR 0x2C5+R I MAK I NMAK SP",
+///         "ST 0x50+W SP",
+///         "ST 0x50+W SR 0x50+R I MAK I NMAK SP",
+///         "ST 0x50+R I MAK I MAK I NMAK SP",
+///     ]
+/// );
+/// ```
+pub struct TransactionalI2c<I2C> {
+    i2c: I2C,
+}
+
+impl<I2C> TransactionalI2c<I2C> {
+    /// Creates a new `TransactionalI2c` wrapping a `Transactional` implementation
+    pub fn new(i2c: I2C) -> Self {
+        Self { i2c }
+    }
+
+    /// Destroys the adapter and returns the wrapped `Transactional` implementation
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+/// Every byte value, to pass the bytes of an iterator as `'static` 1-byte slices
+static BYTES: [u8; 256] = bytes();
+
+const fn bytes() -> [u8; 256] {
+    let mut bytes = [0; 256];
+    let mut i = 0;
+    while i < bytes.len() {
+        bytes[i] = i as u8;
+        i += 1;
+    }
+    bytes
+}
+
+/// Returns an operation writing `byte`
+fn write_byte<'a>(byte: u8) -> Operation<'a> {
+    Operation::Write(core::slice::from_ref(&BYTES[usize::from(byte)]))
+}
+
+impl<I2C: ErrorType> ErrorType for TransactionalI2c<I2C> {
+    type Error = I2C::Error;
+}
+
+impl<A, I2C> Read<A> for TransactionalI2c<I2C>
+where
+    A: AddressMode,
+    I2C: Transactional<A>,
+{
+    fn read(&mut self, address: A, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.exec(address, &mut [Operation::Read(buffer)])
+    }
+}
+
+impl<A, I2C> Write<A> for TransactionalI2c<I2C>
+where
+    A: AddressMode,
+    I2C: Transactional<A>,
+{
+    fn write(&mut self, address: A, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.i2c.exec(address, &mut [Operation::Write(bytes)])
+    }
+}
+
+impl<A, I2C> WriteRead<A> for TransactionalI2c<I2C>
+where
+    A: AddressMode,
+    I2C: Transactional<A>,
+{
+    fn write_read(
+        &mut self,
+        address: A,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.i2c.exec(
+            address,
+            &mut [Operation::Write(bytes), Operation::Read(buffer)],
+        )
+    }
+}
+
+impl<A, I2C> WriteIter<A> for TransactionalI2c<I2C>
+where
+    A: AddressMode,
+    I2C: TransactionalIter<A>,
+{
+    fn write_iter<B>(&mut self, address: A, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        // the empty write addresses the device even if there are no bytes
+        let operations =
+            core::iter::once(Operation::Write(&[])).chain(bytes.into_iter().map(write_byte));
+        self.i2c.exec_iter(address, operations)
+    }
+}
+
+impl<A, I2C> WriteIterRead<A> for TransactionalI2c<I2C>
+where
+    A: AddressMode,
+    I2C: TransactionalIter<A>,
+{
+    fn write_iter_read<B>(
+        &mut self,
+        address: A,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        let operations = core::iter::once(Operation::Write(&[]))
+            .chain(bytes.into_iter().map(write_byte))
+            .chain(core::iter::once(Operation::Read(buffer)));
+        self.i2c.exec_iter(address, operations)
+    }
+}
+
+impl<A, I2C> Transactional<A> for TransactionalI2c<I2C>
+where
+    A: AddressMode,
+    I2C: Transactional<A>,
+{
+    fn exec<'a>(
+        &mut self,
+        address: A,
+        operations: &mut [Operation<'a>],
+    ) -> Result<(), Self::Error> {
+        self.i2c.exec(address, operations)
+    }
+}
+
+impl<A, I2C> TransactionalIter<A> for TransactionalI2c<I2C>
+where
+    A: AddressMode,
+    I2C: TransactionalIter<A>,
+{
+    fn exec_iter<'a, O>(&mut self, address: A, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = Operation<'a>>,
+    {
+        self.i2c.exec_iter(address, operations)
+    }
+}
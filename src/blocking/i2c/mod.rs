@@ -11,7 +11,8 @@
 //! Additionally, the I2C 10-bit address mode has been developed to be fully
 //! backwards compatible with the 7-bit address mode. This allows for a
 //! software-emulated 10-bit addressing implementation if the address mode
-//! is not supported by the hardware.
+//! is not supported by the hardware, see
+//! [`TenBitAddressing`](adapters/struct.TenBitAddressing.html).
 //!
 //! Since 7-bit addressing is the mode of the majority of I2C devices,
 //! `SevenBitAddress` has been set as default mode and thus can be omitted if desired.
@@ -20,16 +21,20 @@
 //!
 //! ### `embedded-hal` implementation for an MCU
 //! Here is an example of an embedded-hal implementation of the `Write` trait
-//! for both modes:
+//! for both modes. The error type is declared once through the `ErrorType`
+//! trait and shared by all the I2C traits implemented for the peripheral:
 //! ```
-//! # use embedded_hal::blocking::i2c::{SevenBitAddress, TenBitAddress, Write};
+//! # use embedded_hal::blocking::i2c::{ErrorKind, ErrorType, SevenBitAddress, TenBitAddress, Write};
 //! /// I2C0 hardware peripheral which supports both 7-bit and 10-bit addressing.
 //! pub struct I2c0;
 //!
+//! impl ErrorType for I2c0 {
+//! #   type Error = ErrorKind;
+//!     // ...
+//! }
+//!
 //! impl Write<SevenBitAddress> for I2c0
 //! {
-//! #   type Error = ();
-//! #
 //!     fn write(&mut self, addr: u8, output: &[u8]) -> Result<(), Self::Error> {
 //!         // ...
 //! #       Ok(())
@@ -38,8 +43,6 @@
 //!
 //! impl Write<TenBitAddress> for I2c0
 //! {
-//! #   type Error = ();
-//! #
 //!     fn write(&mut self, addr: u16, output: &[u8]) -> Result<(), Self::Error> {
 //!         // ...
 //! #       Ok(())
@@ -50,20 +53,27 @@
 //! ### Device driver compatible only with 7-bit addresses
 //!
 //! For demonstration purposes the address mode parameter has been omitted in this example.
+//! Since all I2C traits share the error type of `ErrorType`, a driver using several
+//! of them does not need to constrain their errors to be the same.
 //!
 //! ```
-//! # use embedded_hal::blocking::i2c::WriteRead;
+//! # use embedded_hal::blocking::i2c::{Write, WriteRead};
 //! const ADDR: u8  = 0x15;
 //! # const TEMP_REGISTER: u8 = 0x1;
+//! # const CONFIG_REGISTER: u8 = 0x2;
 //! pub struct TemperatureSensorDriver<I2C> {
 //!     i2c: I2C,
 //! }
 //!
-//! impl<I2C, E> TemperatureSensorDriver<I2C>
+//! impl<I2C> TemperatureSensorDriver<I2C>
 //! where
-//!     I2C: WriteRead<Error = E>,
+//!     I2C: Write + WriteRead,
 //! {
-//!     pub fn read_temperature(&mut self) -> Result<u8, E> {
+//!     pub fn configure(&mut self, config: u8) -> Result<(), I2C::Error> {
+//!         self.i2c.write(ADDR, &[CONFIG_REGISTER, config])
+//!     }
+//!
+//!     pub fn read_temperature(&mut self) -> Result<u8, I2C::Error> {
 //!         let mut temp = [0];
 //!         self.i2c
 //!             .write_read(ADDR, &[TEMP_REGISTER], &mut temp)
@@ -82,11 +92,11 @@
 //!     i2c: I2C,
 //! }
 //!
-//! impl<I2C, E> TemperatureSensorDriver<I2C>
+//! impl<I2C> TemperatureSensorDriver<I2C>
 //! where
-//!     I2C: WriteRead<TenBitAddress, Error = E>,
+//!     I2C: WriteRead<TenBitAddress>,
 //! {
-//!     pub fn read_temperature(&mut self) -> Result<u8, E> {
+//!     pub fn read_temperature(&mut self) -> Result<u8, I2C::Error> {
 //!         let mut temp = [0];
 //!         self.i2c
 //!             .write_read(ADDR, &[TEMP_REGISTER], &mut temp)
@@ -94,9 +104,152 @@
 //!     }
 //! }
 //! ```
+//!
+//! ## Target mode
+//!
+//! The traits above are implemented by I2C controllers (masters). The
+//! [`target`](target/index.html) module provides the traits implemented by I2C targets (slaves).
 
 use crate::private;
 
+pub mod adapters;
+pub mod scan;
+pub mod target;
+
+/// I2C error
+///
+/// Generic drivers can inspect the [`kind`](#tymethod.kind) of an error to react to
+/// specific bus conditions, regardless of the HAL implementation in use:
+///
+/// ```
+/// use embedded_hal::blocking::i2c::{Error, ErrorKind, ErrorType, NoAcknowledgeSource, Write};
+///
+/// /// Returns whether a device answers at `address`
+/// fn is_present<I2C: Write>(i2c: &mut I2C, address: u8) -> Result<bool, I2C::Error> {
+///     match i2c.write(address, &[]) {
+///         Ok(()) => Ok(true),
+///         Err(e) => match e.kind() {
+///             ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address) => Ok(false),
+///             _ => Err(e),
+///         },
+///     }
+/// }
+///
+/// /// An I2C bus where only the device at 0x42 answers
+/// struct I2c;
+///
+/// impl ErrorType for I2c {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl Write for I2c {
+///     fn write(&mut self, address: u8, _bytes: &[u8]) -> Result<(), Self::Error> {
+///         if address == 0x42 {
+///             Ok(())
+///         } else {
+///             Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+///         }
+///     }
+/// }
+///
+/// let mut i2c = I2c;
+/// assert_eq!(is_present(&mut i2c, 0x42), Ok(true));
+/// assert_eq!(is_present(&mut i2c, 0x43), Ok(false));
+/// ```
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic I2C error kind
+    ///
+    /// By using this method, I2C errors freely defined by HAL implementations
+    /// can be converted to a set of generic I2C errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// I2C error kind
+///
+/// This represents a common set of I2C operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common I2C errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Bus error occurred. e.g. A START or a STOP condition is detected and is not
+    /// located after a multiple of 9 SCL clock pulses.
+    Bus,
+    /// The arbitration was lost, e.g. electrical problems with the clock signal
+    ArbitrationLoss,
+    /// A bus operation was not acknowledged, e.g. due to the addressed device not
+    /// being available on the bus or the device not being ready to process requests
+    /// at the moment
+    NoAcknowledge(NoAcknowledgeSource),
+    /// The peripheral receive buffer was overrun
+    Overrun,
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+/// I2C no acknowledge error source
+///
+/// In cases where it is possible, a device should indicate if a no acknowledge
+/// response was received to an address versus a no acknowledge to a data byte.
+/// Where it is not possible to differentiate, `Unknown` should be indicated.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NoAcknowledgeSource {
+    /// The device did not acknowledge its address. The device may be missing.
+    Address,
+    /// The device did not acknowledge the data. It may not be ready to process
+    /// requests at the moment.
+    Data,
+    /// Either the device did not acknowledge its address or the data, but it is
+    /// unknown which.
+    Unknown,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Bus => write!(f, "Bus error occurred"),
+            Self::ArbitrationLoss => write!(f, "The arbitration was lost"),
+            Self::NoAcknowledge(s) => s.fmt(f),
+            Self::Overrun => write!(f, "The peripheral receive buffer was overrun"),
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+impl core::fmt::Display for NoAcknowledgeSource {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Address => write!(f, "The device did not acknowledge its address"),
+            Self::Data => write!(f, "The device did not acknowledge the data"),
+            Self::Unknown => write!(f, "The device did not acknowledge its address or the data"),
+        }
+    }
+}
+
+/// I2C error type trait
+///
+/// This just defines the error type, to be used by the other I2C traits.
+pub trait ErrorType {
+    /// Error type
+    type Error: Error;
+}
+
 /// Address mode (7-bit / 10-bit)
 ///
 /// Note: This trait is sealed and should not be implemented outside of this crate.
@@ -113,10 +266,7 @@ impl AddressMode for SevenBitAddress {}
 impl AddressMode for TenBitAddress {}
 
 /// Blocking read
-pub trait Read<A: AddressMode = SevenBitAddress> {
-    /// Error type
-    type Error;
-
+pub trait Read<A: AddressMode = SevenBitAddress>: ErrorType {
     /// Reads enough bytes from slave with `address` to fill `buffer`
     ///
     /// # I2C Events (contract)
@@ -139,10 +289,7 @@ pub trait Read<A: AddressMode = SevenBitAddress> {
 }
 
 /// Blocking write
-pub trait Write<A: AddressMode = SevenBitAddress> {
-    /// Error type
-    type Error;
-
+pub trait Write<A: AddressMode = SevenBitAddress>: ErrorType {
     /// Writes bytes to slave with address `address`
     ///
     /// # I2C Events (contract)
@@ -163,10 +310,7 @@ pub trait Write<A: AddressMode = SevenBitAddress> {
 }
 
 /// Blocking write (iterator version)
-pub trait WriteIter<A: AddressMode = SevenBitAddress> {
-    /// Error type
-    type Error;
-
+pub trait WriteIter<A: AddressMode = SevenBitAddress>: ErrorType {
     /// Writes bytes to slave with address `address`
     ///
     /// # I2C Events (contract)
@@ -178,10 +322,7 @@ pub trait WriteIter<A: AddressMode = SevenBitAddress> {
 }
 
 /// Blocking write + read
-pub trait WriteRead<A: AddressMode = SevenBitAddress> {
-    /// Error type
-    type Error;
-
+pub trait WriteRead<A: AddressMode = SevenBitAddress>: ErrorType {
     /// Writes bytes to slave with address `address` and then reads enough bytes to fill `buffer` *in a
     /// single transaction*
     ///
@@ -213,10 +354,7 @@ pub trait WriteRead<A: AddressMode = SevenBitAddress> {
 }
 
 /// Blocking write (iterator version) + read
-pub trait WriteIterRead<A: AddressMode = SevenBitAddress> {
-    /// Error type
-    type Error;
-
+pub trait WriteIterRead<A: AddressMode = SevenBitAddress>: ErrorType {
     /// Writes bytes to slave with address `address` and then reads enough bytes to fill `buffer` *in a
     /// single transaction*
     ///
@@ -247,10 +385,7 @@ pub enum Operation<'a> {
 /// Transactional I2C interface.
 ///
 /// This allows combining operations within an I2C transaction.
-pub trait Transactional<A: AddressMode = SevenBitAddress> {
-    /// Error type
-    type Error;
-
+pub trait Transactional<A: AddressMode = SevenBitAddress>: ErrorType {
     /// Execute the provided operations on the I2C bus.
     ///
     /// Transaction contract:
@@ -271,10 +406,7 @@ pub trait Transactional<A: AddressMode = SevenBitAddress> {
 /// Transactional I2C interface (iterator version).
 ///
 /// This allows combining operation within an I2C transaction.
-pub trait TransactionalIter<A: AddressMode = SevenBitAddress> {
-    /// Error type
-    type Error;
-
+pub trait TransactionalIter<A: AddressMode = SevenBitAddress>: ErrorType {
     /// Execute the provided operations on the I2C bus (iterator version).
     ///
     /// Transaction contract:
@@ -0,0 +1,327 @@
+//! I2C bus scanning
+//!
+//! [`scan`](fn.scan.html) and [`scan_read`](fn.scan_read.html) probe a range of addresses and
+//! return the set of [`Devices`](struct.Devices.html) which acknowledged their address. They
+//! don't allocate: the set is a bitmap covering every address of the address mode.
+//!
+//! A missing device is recognized by the [`kind`](../trait.Error.html#tymethod.kind) of the
+//! error of the probe, `NoAcknowledge` of the address (or of an unknown source). Any other error
+//! aborts the scan and is returned.
+//!
+//! # Examples
+//!
+//! ```
+//! use embedded_hal::blocking::i2c::scan::{scan, SEVEN_BIT_ADDRESSES};
+//! use embedded_hal::blocking::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Write};
+//!
+//! /// A bus with an EEPROM at 0x50 and a sensor at 0x76
+//! struct Bus;
+//!
+//! impl ErrorType for Bus {
+//!     type Error = ErrorKind;
+//! }
+//!
+//! impl Write for Bus {
+//!     fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ErrorKind> {
+//!         assert!(bytes.is_empty());
+//!         match address {
+//!             0x50 | 0x76 => Ok(()),
+//!             _ => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+//!         }
+//!     }
+//! }
+//!
+//! let devices = scan(&mut Bus, SEVEN_BIT_ADDRESSES).unwrap();
+//! assert_eq!(devices.len(), 2);
+//! assert!(devices.contains(0x50));
+//! assert!(!devices.contains(0x80));
+//! assert!(devices.iter().eq(vec![0x50, 0x76]));
+//! ```
+
+use super::{
+    AddressMode, Error, ErrorKind, NoAcknowledgeSource, Read, SevenBitAddress, TenBitAddress, Write,
+};
+use core::marker::PhantomData;
+use core::ops::RangeInclusive;
+
+/// 7-bit addresses available for devices
+///
+/// Addresses `0b0000xxx` and `0b1111xxx` are reserved by the I2C specification, e.g. for general
+/// calls or as the header of 10-bit addresses, and are skipped.
+pub const SEVEN_BIT_ADDRESSES: RangeInclusive<SevenBitAddress> = 0x08..=0x77;
+
+/// All the 10-bit addresses
+pub const TEN_BIT_ADDRESSES: RangeInclusive<TenBitAddress> = 0x000..=0x3FF;
+
+mod sealed {
+    use super::{AddressMode, SevenBitAddress, TenBitAddress};
+
+    /// Address mode which can be scanned
+    pub trait Address: AddressMode + Copy {
+        /// Bitmap with one bit per address of the mode
+        type Bitmap: AsRef<[u32]> + AsMut<[u32]> + Copy + Default + Eq;
+
+        /// Converts the address to its index in the bitmap, or `None` if it is out of range
+        fn index(self) -> Option<usize>;
+
+        /// Converts an index in the bitmap to the address
+        fn from_index(index: usize) -> Self;
+    }
+
+    impl Address for SevenBitAddress {
+        type Bitmap = [u32; 4];
+
+        fn index(self) -> Option<usize> {
+            if self <= 0x7F {
+                Some(usize::from(self))
+            } else {
+                None
+            }
+        }
+
+        fn from_index(index: usize) -> Self {
+            index as u8
+        }
+    }
+
+    impl Address for TenBitAddress {
+        type Bitmap = [u32; 32];
+
+        fn index(self) -> Option<usize> {
+            if self <= 0x3FF {
+                Some(usize::from(self))
+            } else {
+                None
+            }
+        }
+
+        fn from_index(index: usize) -> Self {
+            index as u16
+        }
+    }
+}
+
+use self::sealed::Address;
+
+/// Set of devices found on a bus, by address
+///
+/// The set covers every address of the address mode `A`, without allocating.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Devices<A: Address = SevenBitAddress> {
+    bitmap: A::Bitmap,
+    _mode: PhantomData<A>,
+}
+
+impl<A: Address> Devices<A> {
+    /// Creates an empty set
+    pub fn new() -> Self {
+        Self {
+            bitmap: A::Bitmap::default(),
+            _mode: PhantomData,
+        }
+    }
+
+    /// Adds the device at `address` to the set
+    ///
+    /// # Panics
+    ///
+    /// Panics if `address` is out of the range of the address mode, e.g. a 7-bit address above
+    /// `0x7F`.
+    pub fn insert(&mut self, address: A) {
+        let index = address
+            .index()
+            .expect("the address is out of the range of the address mode");
+        self.bitmap.as_mut()[index / 32] |= 1 << (index % 32);
+    }
+
+    /// Returns whether the set contains the device at `address`
+    ///
+    /// Addresses out of the range of the address mode are never contained.
+    pub fn contains(&self, address: A) -> bool {
+        match address.index() {
+            Some(index) => self.bitmap.as_ref()[index / 32] & (1 << (index % 32)) != 0,
+            None => false,
+        }
+    }
+
+    /// Returns the number of devices in the set
+    pub fn len(&self) -> usize {
+        self.bitmap
+            .as_ref()
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.bitmap.as_ref().iter().all(|word| *word == 0)
+    }
+
+    /// Returns an iterator over the addresses of the devices, in ascending order
+    pub fn iter(&self) -> Iter<'_, A> {
+        Iter {
+            devices: self,
+            index: 0,
+        }
+    }
+}
+
+impl<A: Address> Default for Devices<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Address + core::fmt::Debug> core::fmt::Debug for Devices<A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, A: Address> IntoIterator for &'a Devices<A> {
+    type Item = A;
+    type IntoIter = Iter<'a, A>;
+
+    fn into_iter(self) -> Iter<'a, A> {
+        self.iter()
+    }
+}
+
+/// Iterator over the addresses of a set of [`Devices`](struct.Devices.html)
+#[derive(Clone)]
+pub struct Iter<'a, A: Address> {
+    devices: &'a Devices<A>,
+    index: usize,
+}
+
+impl<A: Address> Iterator for Iter<'_, A> {
+    type Item = A;
+
+    fn next(&mut self) -> Option<A> {
+        let bitmap = self.devices.bitmap.as_ref();
+        while self.index < bitmap.len() * 32 {
+            let index = self.index;
+            let word = bitmap[index / 32] >> (index % 32);
+            if word == 0 {
+                // skip the rest of the word
+                self.index = (index / 32 + 1) * 32;
+                continue;
+            }
+
+            let found = index + word.trailing_zeros() as usize;
+            self.index = found + 1;
+            return Some(A::from_index(found));
+        }
+
+        None
+    }
+}
+
+/// Returns whether `error` means that no device acknowledged its address
+fn is_missing<E: Error>(error: &E) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)
+            | ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)
+    )
+}
+
+/// Probes `addresses` with `probe`, adding the devices which acknowledge their address
+fn probe<A, E, R, F>(addresses: R, mut probe: F) -> Result<Devices<A>, E>
+where
+    A: Address,
+    E: Error,
+    R: IntoIterator<Item = A>,
+    F: FnMut(A) -> Result<(), E>,
+{
+    let mut devices = Devices::new();
+    for address in addresses {
+        assert!(
+            address.index().is_some(),
+            "the address is out of the range of the address mode"
+        );
+        match probe(address) {
+            Ok(()) => devices.insert(address),
+            Err(e) if is_missing(&e) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(devices)
+}
+
+/// Scans `addresses` with empty writes
+///
+/// Each address is probed with a write of no bytes: `ST SAD+W SAK SP`, which doesn't change the
+/// state of the devices. Some I2C peripherals cannot send empty writes, see
+/// [`scan_read`](fn.scan_read.html) for them.
+///
+/// Use [`SEVEN_BIT_ADDRESSES`](constant.SEVEN_BIT_ADDRESSES.html) or
+/// [`TEN_BIT_ADDRESSES`](constant.TEN_BIT_ADDRESSES.html) to scan a whole bus.
+///
+/// # Panics
+///
+/// Panics if `addresses` contains an address out of the range of the address mode, e.g. a 7-bit
+/// address above `0x7F`. The addresses before it have been probed.
+pub fn scan<A, I2C, R>(i2c: &mut I2C, addresses: R) -> Result<Devices<A>, I2C::Error>
+where
+    A: Address,
+    I2C: Write<A>,
+    R: IntoIterator<Item = A>,
+{
+    probe(addresses, |address| i2c.write(address, &[]))
+}
+
+/// Scans `addresses` with 1-byte reads
+///
+/// Each address is probed with a read of 1 byte: `ST SAD+R SAK B NMAK SP`. Some devices have
+/// side effects when read, e.g. clearing an interrupt flag, and some write-only devices do not
+/// acknowledge reads: [`scan`](fn.scan.html) should be preferred when the I2C peripheral
+/// supports empty writes.
+///
+/// # Panics
+///
+/// Panics if `addresses` contains an address out of the range of the address mode, e.g. a 7-bit
+/// address above `0x7F`. The addresses before it have been probed.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::i2c::scan::{scan_read, TEN_BIT_ADDRESSES};
+/// use embedded_hal::blocking::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Read};
+///
+/// /// A bus with a device at the 10-bit address 0x2C5, and a stuck device at 0x300
+/// struct Bus;
+///
+/// impl ErrorType for Bus {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl Read<u16> for Bus {
+///     fn read(&mut self, address: u16, buffer: &mut [u8]) -> Result<(), ErrorKind> {
+///         match address {
+///             0x2C5 => {
+///                 buffer[0] = 0x42;
+///                 Ok(())
+///             }
+///             0x300 => Err(ErrorKind::ArbitrationLoss),
+///             _ => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Unknown)),
+///         }
+///     }
+/// }
+///
+/// let devices = scan_read(&mut Bus, 0x200..=0x2FF).unwrap();
+/// assert_eq!(devices.iter().collect::<Vec<u16>>(), [0x2C5]);
+///
+/// // errors other than a missing device abort the scan
+/// assert_eq!(scan_read(&mut Bus, TEN_BIT_ADDRESSES), Err(ErrorKind::ArbitrationLoss));
+/// ```
+pub fn scan_read<A, I2C, R>(i2c: &mut I2C, addresses: R) -> Result<Devices<A>, I2C::Error>
+where
+    A: Address,
+    I2C: Read<A>,
+    R: IntoIterator<Item = A>,
+{
+    probe(addresses, |address| i2c.read(address, &mut [0]))
+}
@@ -0,0 +1,345 @@
+//! Blocking I2C target API
+//!
+//! These traits are implemented by I2C peripherals acting as a target (slave), answering the
+//! transactions of a controller (master). Like the controller traits, they are generic over the
+//! [`AddressMode`](../trait.AddressMode.html) of the addresses the target answers.
+//!
+//! A target first [`listen`](trait.Target.html#tymethod.listen)s on its addresses. Then it
+//! waits for the controller to address it, and either receives the bytes written by the
+//! controller or responds with the bytes read by the controller, depending on the
+//! [`Request`](enum.Request.html).
+//!
+//! HALs which only implement the [`nb`](../../../nb/i2c/target/index.html) target trait can use
+//! [`NbTarget`](struct.NbTarget.html) to obtain the blocking one.
+
+use super::{AddressMode, ErrorType, SevenBitAddress};
+use crate::nb::i2c::target as nb_target;
+
+/// Request of the controller addressing the target
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Request<A> {
+    /// The controller writes to the target at `address`
+    Write {
+        /// Address of the target, among the addresses it listens on
+        address: A,
+        /// Whether the controller addressed the target with a repeated start condition (SR),
+        /// rather than a start condition (ST)
+        repeated_start: bool,
+    },
+    /// The controller reads from the target at `address`
+    Read {
+        /// Address of the target, among the addresses it listens on
+        address: A,
+        /// Whether the controller addressed the target with a repeated start condition (SR),
+        /// rather than a start condition (ST)
+        repeated_start: bool,
+    },
+    /// The controller writes to all the targets with a general call (address 0)
+    GeneralCall {
+        /// Whether the general call follows a repeated start condition (SR), rather than a
+        /// start condition (ST)
+        repeated_start: bool,
+    },
+}
+
+/// Outcome of the [`respond`](trait.Target.html#tymethod.respond) method of a target
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Response {
+    /// Number of bytes sent
+    pub sent: usize,
+    /// Whether the controller did not acknowledge the last byte sent, ending the read
+    pub nacked: bool,
+}
+
+/// I2C target (blocking variant)
+///
+/// # Examples
+///
+/// A device exposing registers, read with `WriteRead` transactions:
+///
+/// ```
+/// use embedded_hal::blocking::i2c::target::{Request, Target};
+/// use embedded_hal::blocking::i2c::ErrorType;
+///
+/// fn serve<T: Target>(target: &mut T, registers: &mut [u8; 4]) -> Result<(), T::Error> {
+///     target.listen(&[0x42])?;
+///     target.set_general_call(true)?;
+///
+///     let mut register = 0;
+///     loop {
+///         match target.wait_for_request()? {
+///             Request::Write { .. } => {
+///                 // register address, followed by values to write
+///                 let mut buffer = [0; 5];
+///                 let count = target.receive(&mut buffer[..1])?;
+///                 if count == 1 && usize::from(buffer[0]) < registers.len() {
+///                     register = usize::from(buffer[0]);
+///                     let count = target.receive(&mut buffer[..registers.len() - register])?;
+///                     registers[register..register + count].copy_from_slice(&buffer[..count]);
+///                 } else if count == 1 {
+///                     // refuse writes to unknown registers
+///                     target.nack()?;
+///                 }
+///             }
+///             Request::Read { .. } => {
+///                 // bytes read past the last register are 0xFF
+///                 let mut response = target.respond(&registers[register..])?;
+///                 while !response.nacked {
+///                     response = target.respond(&[0xFF])?;
+///                 }
+///             }
+///             Request::GeneralCall { .. } => {
+///                 // reset
+///                 *registers = [0; 4];
+///             }
+///         }
+///     }
+/// }
+/// #
+/// # use embedded_hal::blocking::i2c::target::Response;
+/// # use embedded_hal::blocking::i2c::ErrorKind;
+/// # use std::collections::VecDeque;
+/// # /// A target replaying a transaction: `Some(byte)` is a byte written by the controller,
+/// # /// `None` the end of a write, and the controller reads 2 bytes.
+/// # struct Replay {
+/// #     script: VecDeque<Result<Request<u8>, Option<u8>>>,
+/// #     read: Vec<u8>,
+/// # }
+/// # impl ErrorType for Replay {
+/// #     type Error = ErrorKind;
+/// # }
+/// # impl Target for Replay {
+/// #     fn listen(&mut self, addresses: &[u8]) -> Result<(), ErrorKind> {
+/// #         assert_eq!(addresses, [0x42]);
+/// #         Ok(())
+/// #     }
+/// #     fn set_general_call(&mut self, _: bool) -> Result<(), ErrorKind> {
+/// #         Ok(())
+/// #     }
+/// #     fn wait_for_request(&mut self) -> Result<Request<u8>, ErrorKind> {
+/// #         while let Some(Err(_)) = self.script.front() {
+/// #             self.script.pop_front();
+/// #         }
+/// #         match self.script.pop_front() {
+/// #             Some(Ok(request)) => Ok(request),
+/// #             _ => Err(ErrorKind::Other),
+/// #         }
+/// #     }
+/// #     fn receive(&mut self, buffer: &mut [u8]) -> Result<usize, ErrorKind> {
+/// #         let mut count = 0;
+/// #         while count < buffer.len() {
+/// #             match self.script.front() {
+/// #                 Some(Err(Some(byte))) => buffer[count] = *byte,
+/// #                 _ => break,
+/// #             }
+/// #             self.script.pop_front();
+/// #             count += 1;
+/// #         }
+/// #         Ok(count)
+/// #     }
+/// #     fn respond(&mut self, bytes: &[u8]) -> Result<Response, ErrorKind> {
+/// #         self.read.extend_from_slice(&bytes[..2]);
+/// #         Ok(Response { sent: 2, nacked: true })
+/// #     }
+/// #     fn nack(&mut self) -> Result<(), ErrorKind> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// # let script = vec![
+/// #     Ok(Request::Write { address: 0x42, repeated_start: false }),
+/// #     Err(Some(0x01)),
+/// #     Err(Some(0xAA)),
+/// #     Err(None),
+/// #     Ok(Request::Write { address: 0x42, repeated_start: false }),
+/// #     Err(Some(0x00)),
+/// #     Err(None),
+/// #     Ok(Request::Read { address: 0x42, repeated_start: true }),
+/// # ];
+/// # let mut target = Replay { script: script.into(), read: Vec::new() };
+/// # let mut registers = [0x10, 0x11, 0x12, 0x13];
+/// # assert_eq!(serve(&mut target, &mut registers), Err(ErrorKind::Other));
+/// # assert_eq!(registers, [0x10, 0xAA, 0x12, 0x13]);
+/// # assert_eq!(target.read, [0x10, 0xAA]);
+/// ```
+pub trait Target<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Sets the addresses the target answers, replacing the previous ones
+    ///
+    /// The target acknowledges its address when the controller addresses it. Implementations
+    /// supporting fewer addresses than requested should return an error.
+    fn listen(&mut self, addresses: &[A]) -> Result<(), Self::Error>;
+
+    /// Sets whether the target answers general calls
+    fn set_general_call(&mut self, enabled: bool) -> Result<(), Self::Error>;
+
+    /// Blocks until the controller addresses the target, returning its request
+    ///
+    /// The address has been acknowledged when this method returns. Any byte written by the
+    /// controller which was not received by the previous request is not acknowledged.
+    fn wait_for_request(&mut self) -> Result<Request<A>, Self::Error>;
+
+    /// Receives the bytes written by the controller
+    ///
+    /// Acknowledges and stores the bytes written by the controller until `buffer` is full or the
+    /// controller ends the write with a repeated start or stop condition. Returns the number of
+    /// bytes received.
+    ///
+    /// When `buffer` is full, the target holds the bus (stretching the clock) until
+    /// `receive` or [`nack`](#tymethod.nack) is called again.
+    fn receive(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Responds with `bytes` to the controller reading from the target
+    ///
+    /// Sends bytes until the controller does not acknowledge one, meaning that it will not read
+    /// any further, or all of `bytes` have been sent. Returns the number of bytes sent, and
+    /// whether the controller did not acknowledge the last one.
+    ///
+    /// When all of `bytes` have been acknowledged, the target holds the bus (stretching the
+    /// clock) until `respond` is called again. When the controller did not acknowledge the last
+    /// byte, even if it is the last of `bytes`, the read is over and `respond` must not be called
+    /// again before the next request.
+    fn respond(&mut self, bytes: &[u8]) -> Result<Response, Self::Error>;
+
+    /// Does not acknowledge the next byte written by the controller
+    ///
+    /// This signals to the controller that the target refuses any further byte.
+    fn nack(&mut self) -> Result<(), Self::Error>;
+}
+
+/// Blocking I2C target implemented on top of the [`nb` target trait]
+///
+/// [`nb` target trait]: ../../../nb/i2c/target/trait.Target.html
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::i2c::target::{NbTarget, Request, Response, Target};
+/// use embedded_hal::blocking::i2c::{ErrorKind, ErrorType};
+/// use embedded_hal::nb::i2c::target;
+/// use std::collections::VecDeque;
+///
+/// /// A target answering a controller reading 3 bytes, one byte being sent per poll
+/// struct Peripheral {
+///     addressed: bool,
+///     sent: Vec<u8>,
+///     polls: u32,
+/// }
+///
+/// impl ErrorType for Peripheral {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl target::Target for Peripheral {
+///     fn listen(&mut self, _: &[u8]) -> Result<(), ErrorKind> {
+///         Ok(())
+///     }
+///
+///     fn set_general_call(&mut self, _: bool) -> Result<(), ErrorKind> {
+///         Ok(())
+///     }
+///
+///     fn request(&mut self) -> nb::Result<Request<u8>, ErrorKind> {
+///         if self.addressed {
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         self.addressed = true;
+///         Ok(Request::Read { address: 0x42, repeated_start: false })
+///     }
+///
+///     fn read(&mut self) -> nb::Result<Option<u8>, ErrorKind> {
+///         Ok(None)
+///     }
+///
+///     fn write(&mut self, byte: u8) -> nb::Result<bool, ErrorKind> {
+///         self.polls += 1;
+///         if self.polls % 2 == 1 {
+///             return Err(nb::Error::WouldBlock);
+///         }
+///         self.sent.push(byte);
+///         // the controller does not acknowledge the last byte it reads
+///         Ok(self.sent.len() < 3)
+///     }
+///
+///     fn nack(&mut self) -> nb::Result<(), ErrorKind> {
+///         Ok(())
+///     }
+/// }
+///
+/// let mut target = NbTarget::new(Peripheral { addressed: false, sent: Vec::new(), polls: 0 });
+///
+/// assert_eq!(
+///     target.wait_for_request(),
+///     Ok(Request::Read { address: 0x42, repeated_start: false })
+/// );
+/// assert_eq!(target.respond(&[1, 2]), Ok(Response { sent: 2, nacked: false }));
+/// // the controller does not acknowledge the last byte of `bytes`: the read is over
+/// assert_eq!(target.respond(&[3]), Ok(Response { sent: 1, nacked: true }));
+/// assert_eq!(target.release().sent, [1, 2, 3]);
+/// ```
+pub struct NbTarget<T> {
+    target: T,
+}
+
+impl<T> NbTarget<T> {
+    /// Creates a new `NbTarget` wrapping an `nb` target implementation
+    pub fn new(target: T) -> Self {
+        Self { target }
+    }
+
+    /// Destroys the adapter and returns the wrapped `nb` target implementation
+    pub fn release(self) -> T {
+        self.target
+    }
+}
+
+impl<T: ErrorType> ErrorType for NbTarget<T> {
+    type Error = T::Error;
+}
+
+impl<A, T> Target<A> for NbTarget<T>
+where
+    A: AddressMode,
+    T: nb_target::Target<A>,
+{
+    fn listen(&mut self, addresses: &[A]) -> Result<(), Self::Error> {
+        self.target.listen(addresses)
+    }
+
+    fn set_general_call(&mut self, enabled: bool) -> Result<(), Self::Error> {
+        self.target.set_general_call(enabled)
+    }
+
+    fn wait_for_request(&mut self) -> Result<Request<A>, Self::Error> {
+        nb::block!(self.target.request())
+    }
+
+    fn receive(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+        for (count, byte) in buffer.iter_mut().enumerate() {
+            match nb::block!(self.target.read())? {
+                Some(b) => *byte = b,
+                None => return Ok(count),
+            }
+        }
+
+        Ok(buffer.len())
+    }
+
+    fn respond(&mut self, bytes: &[u8]) -> Result<Response, Self::Error> {
+        for (count, &byte) in bytes.iter().enumerate() {
+            if !nb::block!(self.target.write(byte))? {
+                return Ok(Response {
+                    sent: count + 1,
+                    nacked: true,
+                });
+            }
+        }
+
+        Ok(Response {
+            sent: bytes.len(),
+            nacked: false,
+        })
+    }
+
+    fn nack(&mut self) -> Result<(), Self::Error> {
+        nb::block!(self.target.nack())
+    }
+}
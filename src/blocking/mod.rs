@@ -3,12 +3,18 @@
 //! In some cases it's possible to implement these blocking traits on top of one of the core HAL
 //! traits. To save boilerplate when that's the case a `Default` marker trait may be provided.
 //! Implementing that marker trait will opt in your type into a blanket implementation.
+//! Alternatively an adapter type may be provided, which implements the blocking traits for the
+//! core HAL trait implementation it wraps.
 
 pub mod delay;
 pub mod digital;
 pub mod i2c;
+pub mod onewire;
 pub mod pwm;
 pub mod qei;
+pub mod register;
 pub mod serial;
+pub mod shared_bus;
+pub mod smbus;
 pub mod spi;
 pub mod watchdog;
@@ -0,0 +1,437 @@
+//! Blocking 1-Wire API
+//!
+//! The [`OneWire`](trait.OneWire.html) trait provides the 1-Wire bus primitives: reset with
+//! presence detection and time slots to read and write bits. On top of these primitives, it
+//! provides methods to read and write bytes and to send the ROM commands selecting devices.
+//! [`RomSearch`](struct.RomSearch.html) discovers the devices on a bus.
+//!
+//! [`BitBangOneWire`](../../bitbang/onewire/struct.BitBangOneWire.html) implements `OneWire` on
+//! top of an open-drain pin and a delay.
+
+/// 1-Wire error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic 1-Wire error kind
+    ///
+    /// By using this method, 1-Wire errors freely defined by HAL implementations
+    /// can be converted to a set of generic 1-Wire errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// 1-Wire error kind
+///
+/// This represents a common set of 1-Wire operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common 1-Wire errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The bus is held low, e.g. due to a short circuit or a missing pull-up resistor.
+    BusHeldLow,
+    /// No device answered the reset pulse with a presence pulse.
+    NoPresence,
+    /// The CRC of the received data is wrong.
+    CrcMismatch,
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::BusHeldLow => write!(f, "The bus is held low"),
+            Self::NoPresence => write!(f, "No device answered the reset pulse"),
+            Self::CrcMismatch => write!(f, "The CRC of the received data is wrong"),
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+/// 1-Wire error type trait
+///
+/// This just defines the error type, to be used by the other 1-Wire traits.
+pub trait ErrorType {
+    /// Error type
+    type Error: Error;
+}
+
+/// Search ROM command
+pub const SEARCH_ROM: u8 = 0xF0;
+/// Alarm search command, searching the devices whose alarm flag is set
+pub const ALARM_SEARCH: u8 = 0xEC;
+/// Read ROM command, only usable with a single device on the bus
+pub const READ_ROM: u8 = 0x33;
+/// Match ROM command, selecting the device whose ROM code follows
+pub const MATCH_ROM: u8 = 0x55;
+/// Skip ROM command, selecting all the devices
+pub const SKIP_ROM: u8 = 0xCC;
+
+/// Computes the 1-Wire CRC8 (polynomial `x^8 + x^5 + x^4 + 1`) of `data`
+///
+/// The CRC8 of data followed by its own CRC8 is 0, which can be used to check received data:
+///
+/// ```
+/// use embedded_hal::blocking::onewire::crc8;
+///
+/// // ROM code of a DS18B20, whose last byte is the CRC8 of the others
+/// let rom = [0x28, 0xFF, 0x64, 0x1E, 0x0C, 0x3C, 0x04, 0xD1];
+/// assert_eq!(crc8(&rom[..7]), 0xD1);
+/// assert_eq!(crc8(&rom), 0);
+/// ```
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0;
+    for &byte in data {
+        let mut byte = byte;
+        for _ in 0..8 {
+            let mix = (crc ^ byte) & 1;
+            crc >>= 1;
+            if mix != 0 {
+                crc ^= 0x8C;
+            }
+            byte >>= 1;
+        }
+    }
+    crc
+}
+
+/// 64-bit ROM code identifying a 1-Wire device
+///
+/// The ROM code is made of a family code, a 48-bit serial number and the CRC8 of these, sent in
+/// this order on the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Rom(pub [u8; 8]);
+
+impl Rom {
+    /// Returns the family code, identifying the type of the device
+    pub fn family_code(&self) -> u8 {
+        self.0[0]
+    }
+
+    /// Returns the serial number of the device
+    pub fn serial_number(&self) -> [u8; 6] {
+        let mut serial_number = [0; 6];
+        serial_number.copy_from_slice(&self.0[1..7]);
+        serial_number
+    }
+
+    /// Returns whether the CRC8 byte of the ROM code matches the other bytes
+    pub fn is_valid(&self) -> bool {
+        crc8(&self.0) == 0
+    }
+}
+
+/// Error of the ROM commands and of the [`RomSearch`](struct.RomSearch.html)
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CommandError<E> {
+    /// The `OneWire` implementation failed
+    Bus(E),
+    /// No device answered the reset pulse
+    NoPresence,
+    /// A received ROM code has a wrong CRC8
+    CrcMismatch,
+}
+
+impl<E: Error> Error for CommandError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Bus(e) => e.kind(),
+            Self::NoPresence => ErrorKind::NoPresence,
+            Self::CrcMismatch => ErrorKind::CrcMismatch,
+        }
+    }
+}
+
+/// 1-Wire bus master
+///
+/// Bytes are sent and received least significant bit first.
+pub trait OneWire: ErrorType {
+    /// Sends a reset pulse, returning whether a device answered with a presence pulse
+    fn reset(&mut self) -> Result<bool, Self::Error>;
+
+    /// Sends a write time slot for `bit`
+    fn write_bit(&mut self, bit: bool) -> Result<(), Self::Error>;
+
+    /// Sends a read time slot, returning the bit sent by the devices
+    ///
+    /// When several devices send a bit, the result is the logical AND of their bits.
+    fn read_bit(&mut self) -> Result<bool, Self::Error>;
+
+    /// Writes a byte
+    fn write_byte(&mut self, byte: u8) -> Result<(), Self::Error> {
+        for i in 0..8 {
+            self.write_bit(byte & (1 << i) != 0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a byte
+    fn read_byte(&mut self) -> Result<u8, Self::Error> {
+        let mut byte = 0;
+        for i in 0..8 {
+            if self.read_bit()? {
+                byte |= 1 << i;
+            }
+        }
+
+        Ok(byte)
+    }
+
+    /// Runs a step of a ROM search
+    ///
+    /// Reads a bit of the ROM codes of the devices taking part in the search, then its
+    /// complement. Then writes the bit value selecting the devices which stay in the search:
+    /// the value read if it is the same for all the devices, `direction` otherwise. Returns the
+    /// two bits read and the bit written.
+    ///
+    /// Implementations should override this method if the hardware runs search steps itself,
+    /// like the triplet command of the DS2482 bridges.
+    fn triplet(&mut self, direction: bool) -> Result<(bool, bool, bool), Self::Error> {
+        let bit = self.read_bit()?;
+        let complement = self.read_bit()?;
+        let direction = if bit != complement { bit } else { direction };
+        self.write_bit(direction)?;
+        Ok((bit, complement, direction))
+    }
+
+    /// Resets the bus and selects the device with the given ROM code
+    fn match_rom(&mut self, rom: &Rom) -> Result<(), CommandError<Self::Error>> {
+        select(self, MATCH_ROM)?;
+        for &byte in rom.0.iter() {
+            self.write_byte(byte).map_err(CommandError::Bus)?;
+        }
+
+        Ok(())
+    }
+
+    /// Resets the bus and selects all the devices
+    fn skip_rom(&mut self) -> Result<(), CommandError<Self::Error>> {
+        select(self, SKIP_ROM)
+    }
+
+    /// Resets the bus and reads the ROM code of the only device on the bus
+    ///
+    /// If several devices are on the bus, they answer at the same time, which results in a
+    /// `CrcMismatch` error most of the time.
+    fn read_rom(&mut self) -> Result<Rom, CommandError<Self::Error>> {
+        select(self, READ_ROM)?;
+        let mut rom = Rom([0; 8]);
+        for byte in rom.0.iter_mut() {
+            *byte = self.read_byte().map_err(CommandError::Bus)?;
+        }
+
+        if rom.is_valid() {
+            Ok(rom)
+        } else {
+            Err(CommandError::CrcMismatch)
+        }
+    }
+}
+
+/// Resets the bus and sends a ROM command
+fn select<W>(bus: &mut W, command: u8) -> Result<(), CommandError<W::Error>>
+where
+    W: OneWire + ?Sized,
+{
+    if !bus.reset().map_err(CommandError::Bus)? {
+        return Err(CommandError::NoPresence);
+    }
+
+    bus.write_byte(command).map_err(CommandError::Bus)
+}
+
+/// Search of the ROM codes of the devices on a bus
+///
+/// Each call to [`next`](#method.next) runs a search ROM (or alarm search) command and returns
+/// the ROM code of a new device, in increasing order of the ROM codes read least significant
+/// bit first, until all the devices have been found.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::onewire::{ErrorType, OneWire, Rom, RomSearch, SEARCH_ROM};
+/// use core::convert::Infallible;
+///
+/// /// A bus with devices taking part in searches
+/// struct Bus {
+///     devices: Vec<Rom>,
+///     /// Devices which are still in the search
+///     active: Vec<bool>,
+///     command: Option<u8>,
+///     bits: usize,
+/// }
+///
+/// impl Bus {
+///     fn bit(rom: &Rom, i: usize) -> bool {
+///         rom.0[i / 8] & (1 << (i % 8)) != 0
+///     }
+/// }
+///
+/// impl ErrorType for Bus {
+///     type Error = Infallible;
+/// }
+///
+/// impl OneWire for Bus {
+///     fn reset(&mut self) -> Result<bool, Infallible> {
+///         self.active = vec![true; self.devices.len()];
+///         self.command = None;
+///         self.bits = 0;
+///         Ok(!self.devices.is_empty())
+///     }
+///
+///     fn write_bit(&mut self, bit: bool) -> Result<(), Infallible> {
+///         if self.command.is_none() {
+///             // command bits, assuming a search ROM command
+///             self.bits += 1;
+///             if self.bits == 8 {
+///                 self.command = Some(SEARCH_ROM);
+///                 self.bits = 0;
+///             }
+///             return Ok(());
+///         }
+///         // devices whose bit differs leave the search
+///         let i = self.bits / 3;
+///         for (rom, active) in self.devices.iter().zip(self.active.iter_mut()) {
+///             *active &= Bus::bit(rom, i) == bit;
+///         }
+///         self.bits += 1;
+///         Ok(())
+///     }
+///
+///     fn read_bit(&mut self) -> Result<bool, Infallible> {
+///         // the bit, then its complement, of each active device
+///         let (i, complement) = (self.bits / 3, self.bits % 3 == 1);
+///         self.bits += 1;
+///         Ok(self
+///             .devices
+///             .iter()
+///             .zip(self.active.iter())
+///             .filter(|(_, &active)| active)
+///             .all(|(rom, _)| Bus::bit(rom, i) != complement))
+///     }
+/// }
+///
+/// let devices = vec![
+///     Rom([0x28, 0xFF, 0x64, 0x1E, 0x0C, 0x3C, 0x04, 0xD1]),
+///     Rom([0x28, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x29]),
+///     Rom([0x10, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0xCC]),
+/// ];
+/// let mut bus = Bus { devices: devices.clone(), active: Vec::new(), command: None, bits: 0 };
+///
+/// let mut search = RomSearch::new();
+/// let mut found = Vec::new();
+/// while let Some(rom) = search.next(&mut bus).unwrap() {
+///     found.push(rom);
+/// }
+/// found.sort();
+/// let mut expected = devices;
+/// expected.sort();
+/// assert_eq!(found, expected);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RomSearch {
+    command: u8,
+    rom: [u8; 8],
+    /// Index of the last bit where the `false` branch was taken while both were possible, plus
+    /// one, or 0 if there is no such bit
+    last_discrepancy: usize,
+    done: bool,
+}
+
+impl Default for RomSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RomSearch {
+    /// Creates a search of all the devices on the bus
+    pub fn new() -> Self {
+        Self::with_command(SEARCH_ROM)
+    }
+
+    /// Creates a search of the devices whose alarm flag is set
+    pub fn new_alarm() -> Self {
+        Self::with_command(ALARM_SEARCH)
+    }
+
+    fn with_command(command: u8) -> Self {
+        Self {
+            command,
+            rom: [0; 8],
+            last_discrepancy: 0,
+            done: false,
+        }
+    }
+
+    /// Finds the next device
+    ///
+    /// Returns `None` once all the devices have been found, or if no device takes part in the
+    /// search.
+    pub fn next<W: OneWire>(&mut self, bus: &mut W) -> Result<Option<Rom>, CommandError<W::Error>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        match select(bus, self.command) {
+            Err(CommandError::NoPresence) => {
+                self.done = true;
+                return Ok(None);
+            }
+            result => result?,
+        }
+
+        let mut discrepancy = 0;
+        for i in 0..64 {
+            let (byte, mask) = (i / 8, 1 << (i % 8));
+            let previous = self.rom[byte] & mask != 0;
+            // take the `true` branch at the last discrepancy, and the previous branches before it
+            let direction = match (i + 1).cmp(&self.last_discrepancy) {
+                core::cmp::Ordering::Less => previous,
+                core::cmp::Ordering::Equal => true,
+                core::cmp::Ordering::Greater => false,
+            };
+
+            let (bit, complement, direction) = bus.triplet(direction).map_err(CommandError::Bus)?;
+            if bit && complement {
+                // no device took part in the search
+                self.done = true;
+                return Ok(None);
+            }
+            if !bit && !complement && !direction {
+                discrepancy = i + 1;
+            }
+
+            if direction {
+                self.rom[byte] |= mask;
+            } else {
+                self.rom[byte] &= !mask;
+            }
+        }
+
+        self.last_discrepancy = discrepancy;
+        self.done = discrepancy == 0;
+
+        let rom = Rom(self.rom);
+        if rom.is_valid() {
+            Ok(Some(rom))
+        } else {
+            Err(CommandError::CrcMismatch)
+        }
+    }
+}
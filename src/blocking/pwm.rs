@@ -1,5 +1,58 @@
 //! Pulse Width Modulation
 
+/// PWM error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic PWM error kind
+    ///
+    /// By using this method, PWM errors freely defined by HAL implementations
+    /// can be converted to a set of generic PWM errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// PWM error kind
+///
+/// This represents a common set of PWM operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common PWM errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+/// PWM error type trait
+///
+/// This just defines the error type, to be used by the other PWM traits.
+pub trait ErrorType {
+    /// Error type
+    type Error: Error;
+}
+
 /// Pulse Width Modulation
 ///
 /// # Examples
@@ -34,8 +87,10 @@
 /// # impl U32Ext for u32 { fn khz(self) -> KiloHertz { KiloHertz(self) } }
 /// # enum Channel { _1, _2 }
 /// # struct Pwm1;
-/// # impl hal::blocking::pwm::Pwm for Pwm1 {
+/// # impl hal::blocking::pwm::ErrorType for Pwm1 {
 /// #     type Error = Infallible;
+/// # }
+/// # impl hal::blocking::pwm::Pwm for Pwm1 {
 /// #     type Channel = Channel;
 /// #     type Time = KiloHertz;
 /// #     type Duty = u16;
@@ -50,10 +105,7 @@
 /// ```
 // unproven reason: pre-singletons API. The `PwmPin` trait seems more useful because it models independent
 // PWM channels. Here a certain number of channels are multiplexed in a single implementer.
-pub trait Pwm {
-    /// Enumeration of `Pwm` errors
-    type Error;
-
+pub trait Pwm: ErrorType {
     /// Enumeration of channels that can be used with this `Pwm` interface
     ///
     /// If your `Pwm` interface has no channels you can use the type `()`
@@ -99,10 +151,7 @@ pub trait Pwm {
 /// A single PWM channel / pin
 ///
 /// See `Pwm` for details
-pub trait PwmPin {
-    /// Enumeration of `PwmPin` errors
-    type Error;
-
+pub trait PwmPin: ErrorType {
     /// Type for the `duty` methods
     ///
     /// The implementer is free to choose a float / percentage representation
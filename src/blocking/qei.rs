@@ -46,8 +46,10 @@
 /// #     fn direction(&self) -> Result<::hal::blocking::qei::Direction, Self::Error> { unimplemented!() }
 /// # }
 /// # struct Timer6;
-/// # impl hal::nb::timer::CountDown for Timer6 {
+/// # impl hal::nb::timer::ErrorType for Timer6 {
 /// #     type Error = Infallible;
+/// # }
+/// # impl hal::nb::timer::CountDown for Timer6 {
 /// #     type Time = Seconds;
 /// #     fn start<T>(&mut self, _: T) -> Result<(), Infallible> where T: Into<Seconds> { Ok(()) }
 /// #     fn wait(&mut self) -> ::nb::Result<(), Infallible> { Ok(()) }
@@ -0,0 +1,446 @@
+//! Register access
+//!
+//! Most I2C and SPI devices are controlled through registers: the controller sends the address
+//! of a register, then writes or reads its value. The [`RegisterInterface`] trait provides
+//! these register accesses to drivers, whatever the bus of the device:
+//!
+//! - [`I2cRegisters`] implements it for an I2C device, over the blocking [`i2c::WriteRead`] and
+//!   [`i2c::Transactional`] traits,
+//! - [`SpiRegisters`] implements it for an SPI device, over [`spi::SpiDevice`].
+//!
+//! The [`Config`] of these implementations describes the conventions of the device: width of the
+//! register addresses, byte order of multi-byte values, and the flags set in the register
+//! address to read, to write, or to access several registers at once.
+//!
+//! [`RegisterInterface`]: trait.RegisterInterface.html
+//! [`I2cRegisters`]: struct.I2cRegisters.html
+//! [`SpiRegisters`]: struct.SpiRegisters.html
+//! [`i2c::WriteRead`]: ../i2c/trait.WriteRead.html
+//! [`i2c::Transactional`]: ../i2c/trait.Transactional.html
+//! [`spi::SpiDevice`]: ../spi/trait.SpiDevice.html
+//! [`Config`]: struct.Config.html
+
+use crate::blocking::{i2c, spi};
+
+/// Width of the register addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressWidth {
+    /// 8-bit register addresses
+    Eight,
+    /// 16-bit register addresses, sent most significant byte first
+    Sixteen,
+}
+
+/// Byte order of the values spanning several registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Most significant byte first, i.e. at the lowest register address
+    Big,
+    /// Least significant byte first, i.e. at the lowest register address
+    Little,
+}
+
+/// Register conventions of a device
+///
+/// The flags are ORed into the register address sent to the device. For example, many SPI
+/// devices expect the most significant bit of the register address to be set for reads
+/// (`read_flag: 0x80`), and some devices only increment the register address during an access
+/// to several registers if a given bit is set (`auto_increment: 0x40` on SPI, `0x80` on I2C,
+/// for some accelerometers).
+///
+/// The default configuration uses 8-bit register addresses, big endian values and no flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    /// Width of the register addresses
+    ///
+    /// With 8-bit register addresses, the 8 most significant bits of the register addresses
+    /// and flags are ignored.
+    pub address_width: AddressWidth,
+    /// Byte order of the values spanning several registers
+    pub endianness: Endianness,
+    /// Flag set in the register address to access several registers at once
+    pub auto_increment: u16,
+    /// Flag set in the register address to read registers
+    pub read_flag: u16,
+    /// Flag set in the register address to write registers
+    pub write_flag: u16,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            address_width: AddressWidth::Eight,
+            endianness: Endianness::Big,
+            auto_increment: 0,
+            read_flag: 0,
+            write_flag: 0,
+        }
+    }
+}
+
+impl Config {
+    /// Encodes the address of `register` to access `length` registers, with `flag` set
+    fn address(&self, register: u16, flag: u16, length: usize) -> ([u8; 2], usize) {
+        let mut address = register | flag;
+        if length > 1 {
+            address |= self.auto_increment;
+        }
+
+        match self.address_width {
+            AddressWidth::Eight => ([address as u8, 0], 1),
+            AddressWidth::Sixteen => (address.to_be_bytes(), 2),
+        }
+    }
+}
+
+/// Register access of a device
+///
+/// Only `endianness`, `read_registers` and `write_registers` must be implemented. The other
+/// methods are provided on top of them.
+///
+/// # Examples
+///
+/// A driver for a device with 8-bit registers, over I2C:
+///
+/// ```
+/// use embedded_hal::blocking::i2c::{self, ErrorType, Operation, Transactional, WriteRead};
+/// use embedded_hal::blocking::register::{Config, I2cRegisters, RegisterInterface};
+/// use core::convert::Infallible;
+///
+/// const TEMPERATURE: u16 = 0x00;
+/// const CONFIG: u16 = 0x01;
+/// const LIMITS: u16 = 0x02;
+///
+/// /// Reads the temperature, with a resolution of 1/256 °C
+/// fn temperature<R: RegisterInterface>(device: &mut R) -> Result<i16, R::Error> {
+///     Ok(device.read_u16(TEMPERATURE)? as i16)
+/// }
+///
+/// /// Sets the 2-bit resolution field of the configuration register (bits 5 and 6)
+/// fn set_resolution<R: RegisterInterface>(device: &mut R, resolution: u8) -> Result<(), R::Error> {
+///     device.modify_register(CONFIG, 0b0110_0000, resolution << 5)
+/// }
+///
+/// /// A device at address 0x48 with 8 registers, which increments its register address after
+/// /// each byte
+/// struct Sensor {
+///     registers: [u8; 8],
+/// }
+///
+/// impl ErrorType for Sensor {
+///     type Error = Infallible;
+/// }
+///
+/// impl WriteRead for Sensor {
+///     fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Infallible> {
+///         assert_eq!((address, bytes.len()), (0x48, 1));
+///         let start = usize::from(bytes[0]);
+///         buffer.copy_from_slice(&self.registers[start..start + buffer.len()]);
+///         Ok(())
+///     }
+/// }
+///
+/// impl Transactional for Sensor {
+///     fn exec(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Infallible> {
+///         assert_eq!(address, 0x48);
+///         let mut bytes = operations.iter().flat_map(|operation| match operation {
+///             Operation::Write(bytes) => bytes.iter().copied(),
+///             Operation::Read(_) => unimplemented!(),
+///         });
+///         let start = usize::from(bytes.next().unwrap());
+///         for (register, byte) in self.registers[start..].iter_mut().zip(bytes) {
+///             *register = byte;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let sensor = Sensor { registers: [0x19, 0x80, 0x9F, 0, 0, 0, 0, 0] };
+/// let mut device = I2cRegisters::new(sensor, 0x48, Config::default());
+///
+/// assert_eq!(temperature(&mut device), Ok(0x1980));
+/// set_resolution(&mut device, 0b10).unwrap();
+/// assert_eq!(device.read_register(CONFIG), Ok(0b1100_0000));
+///
+/// device.write_registers(LIMITS, &[0x50, 0x00, 0x4B, 0x00]).unwrap();
+/// let mut limits = [0; 4];
+/// device.read_registers(LIMITS, &mut limits).unwrap();
+/// assert_eq!(limits, [0x50, 0x00, 0x4B, 0x00]);
+/// ```
+pub trait RegisterInterface {
+    /// Error type
+    type Error: core::fmt::Debug;
+
+    /// Returns the byte order of the values spanning several registers
+    fn endianness(&self) -> Endianness;
+
+    /// Reads consecutive registers, starting at `register`, to fill `buffer`
+    fn read_registers(&mut self, register: u16, buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Writes `bytes` to consecutive registers, starting at `register`
+    fn write_registers(&mut self, register: u16, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Reads a register
+    fn read_register(&mut self, register: u16) -> Result<u8, Self::Error> {
+        let mut buffer = [0];
+        self.read_registers(register, &mut buffer)?;
+        Ok(buffer[0])
+    }
+
+    /// Writes a register
+    fn write_register(&mut self, register: u16, value: u8) -> Result<(), Self::Error> {
+        self.write_registers(register, &[value])
+    }
+
+    /// Updates the bits of a register set in `mask` to the corresponding bits of `value`
+    ///
+    /// The register is read, then written with its other bits unchanged. This is not atomic:
+    /// the device must not change the register in between.
+    fn modify_register(&mut self, register: u16, mask: u8, value: u8) -> Result<(), Self::Error> {
+        let current = self.read_register(register)?;
+        self.write_register(register, (current & !mask) | (value & mask))
+    }
+
+    /// Reads a 16-bit value from 2 consecutive registers, starting at `register`
+    fn read_u16(&mut self, register: u16) -> Result<u16, Self::Error> {
+        let mut buffer = [0; 2];
+        self.read_registers(register, &mut buffer)?;
+        Ok(match self.endianness() {
+            Endianness::Big => u16::from_be_bytes(buffer),
+            Endianness::Little => u16::from_le_bytes(buffer),
+        })
+    }
+
+    /// Writes a 16-bit value to 2 consecutive registers, starting at `register`
+    fn write_u16(&mut self, register: u16, value: u16) -> Result<(), Self::Error> {
+        let bytes = match self.endianness() {
+            Endianness::Big => value.to_be_bytes(),
+            Endianness::Little => value.to_le_bytes(),
+        };
+        self.write_registers(register, &bytes)
+    }
+
+    /// Reads a 32-bit value from 4 consecutive registers, starting at `register`
+    fn read_u32(&mut self, register: u16) -> Result<u32, Self::Error> {
+        let mut buffer = [0; 4];
+        self.read_registers(register, &mut buffer)?;
+        Ok(match self.endianness() {
+            Endianness::Big => u32::from_be_bytes(buffer),
+            Endianness::Little => u32::from_le_bytes(buffer),
+        })
+    }
+
+    /// Writes a 32-bit value to 4 consecutive registers, starting at `register`
+    fn write_u32(&mut self, register: u16, value: u32) -> Result<(), Self::Error> {
+        let bytes = match self.endianness() {
+            Endianness::Big => value.to_be_bytes(),
+            Endianness::Little => value.to_le_bytes(),
+        };
+        self.write_registers(register, &bytes)
+    }
+}
+
+/// Registers of an I2C device
+///
+/// Registers are read with a [`WriteRead`](../i2c/trait.WriteRead.html) transaction: the
+/// register address is written, then the values are read after a repeated start. Registers are
+/// written with a single write of the register address followed by the values, using
+/// [`Transactional`](../i2c/trait.Transactional.html).
+///
+/// See [`RegisterInterface`](trait.RegisterInterface.html) for an example.
+pub struct I2cRegisters<I2C> {
+    i2c: I2C,
+    address: u8,
+    config: Config,
+}
+
+impl<I2C> I2cRegisters<I2C> {
+    /// Creates a new `I2cRegisters` for the device at `address` on the `i2c` bus
+    pub fn new(i2c: I2C, address: u8, config: Config) -> Self {
+        Self {
+            i2c,
+            address,
+            config,
+        }
+    }
+
+    /// Destroys the register interface and returns the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C> RegisterInterface for I2cRegisters<I2C>
+where
+    I2C: i2c::WriteRead + i2c::Transactional,
+{
+    type Error = I2C::Error;
+
+    fn endianness(&self) -> Endianness {
+        self.config.endianness
+    }
+
+    fn read_registers(&mut self, register: u16, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let (address, length) = self
+            .config
+            .address(register, self.config.read_flag, buffer.len());
+        self.i2c
+            .write_read(self.address, &address[..length], buffer)
+    }
+
+    fn write_registers(&mut self, register: u16, bytes: &[u8]) -> Result<(), Self::Error> {
+        let (address, length) = self
+            .config
+            .address(register, self.config.write_flag, bytes.len());
+        self.i2c.exec(
+            self.address,
+            &mut [
+                i2c::Operation::Write(&address[..length]),
+                i2c::Operation::Write(bytes),
+            ],
+        )
+    }
+}
+
+/// Registers of an SPI device
+///
+/// Every access is a single [`SpiDevice`](../spi/trait.SpiDevice.html) transaction: the register
+/// address is written, then the values are written, or read while zeros are written.
+///
+/// # Examples
+///
+/// An accelerometer whose registers are read with the most significant bit of the register
+/// address set, and which increments the register address when bit 6 is set:
+///
+/// ```
+/// use embedded_hal::blocking::digital::adapters::NoPin;
+/// use embedded_hal::blocking::register::{Config, Endianness, RegisterInterface, SpiRegisters};
+/// use embedded_hal::blocking::spi::{self, ExclusiveDevice, Operation, SpiBus, Transactional, Transfer, Write};
+/// use core::convert::Infallible;
+///
+/// const CTRL_REG1: u16 = 0x20;
+/// const OUT_X_L: u16 = 0x28;
+///
+/// /// The registers of the accelerometer, behind an SPI bus with a single device on it
+/// struct Bus {
+///     registers: [u8; 0x30],
+/// }
+///
+/// impl Transactional<u8> for Bus {
+///     fn exec(&mut self, operations: &mut [Operation<'_, u8>]) -> Result<(), Infallible> {
+///         let (address, data) = operations.split_first_mut().unwrap();
+///         let address = match address {
+///             Operation::Write(&[address]) => address,
+///             _ => unimplemented!(),
+///         };
+///         let read = address & 0x80 != 0;
+///         let increment = address & 0x40 != 0;
+///         let mut register = usize::from(address & 0x3F);
+///         for operation in data {
+///             match operation {
+///                 Operation::Transfer(words) if read => {
+///                     for word in words.iter_mut() {
+///                         *word = self.registers[register];
+///                         register += increment as usize;
+///                     }
+///                 }
+///                 Operation::Write(words) if !read => {
+///                     for word in words.iter() {
+///                         self.registers[register] = *word;
+///                         register += increment as usize;
+///                     }
+///                 }
+///                 _ => unimplemented!(),
+///             }
+///         }
+///         Ok(())
+///     }
+/// }
+/// # impl spi::ErrorType for Bus {
+/// #     type Error = Infallible;
+/// # }
+/// # impl Transfer<u8> for Bus {
+/// #     fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Infallible> {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// # impl Write<u8> for Bus {
+/// #     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+/// #         unimplemented!()
+/// #     }
+/// # }
+/// # impl SpiBus<u8> for Bus {
+/// #     fn flush(&mut self) -> Result<(), Infallible> {
+/// #         Ok(())
+/// #     }
+/// # }
+///
+/// let mut bus = Bus { registers: [0; 0x30] };
+/// bus.registers[0x28..0x2E].copy_from_slice(&[0x40, 0x01, 0xC0, 0xFF, 0x00, 0x40]);
+///
+/// let config = Config {
+///     endianness: Endianness::Little,
+///     auto_increment: 0x40,
+///     read_flag: 0x80,
+///     ..Config::default()
+/// };
+/// let mut device = SpiRegisters::new(ExclusiveDevice::new(bus, NoPin::new()), config);
+///
+/// let mut acceleration = [0; 6];
+/// device.read_registers(OUT_X_L, &mut acceleration).unwrap();
+/// assert_eq!(acceleration, [0x40, 0x01, 0xC0, 0xFF, 0x00, 0x40]);
+/// assert_eq!(device.read_u16(OUT_X_L), Ok(0x0140));
+///
+/// device.write_register(CTRL_REG1, 0x57).unwrap();
+/// assert_eq!(device.read_register(CTRL_REG1), Ok(0x57));
+/// ```
+pub struct SpiRegisters<D> {
+    device: D,
+    config: Config,
+}
+
+impl<D> SpiRegisters<D> {
+    /// Creates a new `SpiRegisters` for an SPI device
+    pub fn new(device: D, config: Config) -> Self {
+        Self { device, config }
+    }
+
+    /// Destroys the register interface and returns the SPI device
+    pub fn release(self) -> D {
+        self.device
+    }
+}
+
+impl<D> RegisterInterface for SpiRegisters<D>
+where
+    D: spi::SpiDevice<u8>,
+{
+    type Error = D::Error;
+
+    fn endianness(&self) -> Endianness {
+        self.config.endianness
+    }
+
+    fn read_registers(&mut self, register: u16, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let (address, length) = self
+            .config
+            .address(register, self.config.read_flag, buffer.len());
+        for byte in buffer.iter_mut() {
+            *byte = 0;
+        }
+        self.device.exec(&mut [
+            spi::Operation::Write(&address[..length]),
+            spi::Operation::Transfer(buffer),
+        ])
+    }
+
+    fn write_registers(&mut self, register: u16, bytes: &[u8]) -> Result<(), Self::Error> {
+        let (address, length) = self
+            .config
+            .address(register, self.config.write_flag, bytes.len());
+        self.device.exec(&mut [
+            spi::Operation::Write(&address[..length]),
+            spi::Operation::Write(bytes),
+        ])
+    }
+}
@@ -1,10 +1,81 @@
 //! Blocking serial API
+//!
+//! HALs which only implement the [`nb::serial`](../../nb/serial/index.html) traits can use
+//! [`NbSerial`](struct.NbSerial.html) to obtain the blocking serial traits.
 
-/// Write half of a serial interface (blocking variant)
-pub trait Write<Word> {
-    /// The type of error that can occur when writing
-    type Error;
+use crate::nb::serial as nb_serial;
+
+/// Serial error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic serial error kind
+    ///
+    /// By using this method, serial errors freely defined by HAL implementations
+    /// can be converted to a set of generic serial errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// Serial error kind
+///
+/// This represents a common set of serial operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common serial errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The peripheral receive buffer was overrun.
+    Overrun,
+    /// Received data does not conform to the peripheral configuration.
+    /// Can be caused by a misconfigured device on either end of the serial line.
+    Framing,
+    /// Parity check failed.
+    Parity,
+    /// Serial line is too noisy to read valid data.
+    Noise,
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
 
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overrun => write!(f, "The peripheral receive buffer was overrun"),
+            Self::Parity => write!(f, "Parity check failed"),
+            Self::Noise => write!(f, "Serial line is too noisy to read valid data"),
+            Self::Framing => write!(
+                f,
+                "Received data does not conform to the peripheral configuration"
+            ),
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+/// Serial error type trait
+///
+/// This just defines the error type, to be used by the other serial traits.
+pub trait ErrorType {
+    /// Error type
+    type Error: Error;
+}
+
+/// Write half of a serial interface (blocking variant)
+pub trait Write<Word>: ErrorType {
     /// Writes a slice, blocking until everything has been written
     ///
     /// An implementation can choose to buffer the write, returning `Ok(())`
@@ -18,3 +89,183 @@ pub trait Write<Word> {
     /// Block until the serial interface has sent all buffered words
     fn flush(&mut self) -> Result<(), Self::Error>;
 }
+
+/// Read half of a serial interface (blocking variant)
+pub trait Read<Word>: ErrorType {
+    /// Reads words until `buffer` is full, blocking until all of them have been received
+    fn read_exact(&mut self, buffer: &mut [Word]) -> Result<(), Self::Error>;
+
+    /// Reads the words already received, blocking until there is at least one
+    ///
+    /// Blocks until at least one word has been received, unless `buffer` is empty. Then reads
+    /// the words which have already been received, e.g. into a FIFO, until `buffer` is full,
+    /// without waiting for any further word. Returns the number of words read into `buffer`.
+    ///
+    /// This does not detect the end of a message: the next word of a message may still be in
+    /// transit when this method returns.
+    ///
+    /// If an error occurs after some words have been read, the number of words read is returned
+    /// and the error is reported by the next call.
+    fn read(&mut self, buffer: &mut [Word]) -> Result<usize, Self::Error>;
+}
+
+/// Blocking serial traits implemented on top of the [`nb::serial`] traits
+///
+/// This type implements `Read` for any `nb::serial::Read` implementation and `Write` for any
+/// `nb::serial::Write` implementation. After the first word, `read` only reads the words which
+/// the `nb::serial::Read` implementation returns without `WouldBlock`, i.e. the words already
+/// received. An error returned after the first word is kept and reported by the next call to
+/// `read` or `read_exact`.
+///
+/// [`nb::serial`]: ../../nb/serial/index.html
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::serial::{ErrorKind, ErrorType, NbSerial, Read, Write};
+/// use embedded_hal::nb::serial;
+/// use std::collections::VecDeque;
+///
+/// /// A serial interface receiving words from a queue, where `None` stands for no word available
+/// struct Serial {
+///     rx: VecDeque<Option<u8>>,
+///     tx: Vec<u8>,
+/// }
+///
+/// impl ErrorType for Serial {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl serial::Read<u8> for Serial {
+///     fn read(&mut self) -> nb::Result<u8, ErrorKind> {
+///         match self.rx.pop_front() {
+///             Some(Some(word)) => Ok(word),
+///             Some(None) => Err(nb::Error::WouldBlock),
+///             None => Err(nb::Error::Other(ErrorKind::Overrun)),
+///         }
+///     }
+/// }
+///
+/// impl serial::Write<u8> for Serial {
+///     fn write(&mut self, word: u8) -> nb::Result<(), ErrorKind> {
+///         self.tx.push(word);
+///         Ok(())
+///     }
+///
+///     fn flush(&mut self) -> nb::Result<(), ErrorKind> {
+///         Ok(())
+///     }
+/// }
+///
+/// let rx = vec![None, Some(1), Some(2), None, None, Some(3), None, Some(4), Some(5), Some(6)];
+/// let mut serial = NbSerial::new(Serial { rx: rx.into(), tx: Vec::new() });
+///
+/// // `read` waits for the first word and stops when no more word is available
+/// let mut buffer = [0; 4];
+/// assert_eq!(serial.read(&mut buffer), Ok(2));
+/// assert_eq!(buffer[..2], [1, 2]);
+///
+/// // `read_exact` waits until the buffer is full
+/// let mut buffer = [0; 3];
+/// assert_eq!(serial.read_exact(&mut buffer), Ok(()));
+/// assert_eq!(buffer, [3, 4, 5]);
+///
+/// // an error following received words is reported by the next call
+/// assert_eq!(serial.read(&mut buffer), Ok(1));
+/// assert_eq!(buffer[..1], [6]);
+/// assert_eq!(serial.read(&mut buffer), Err(ErrorKind::Overrun));
+///
+/// // errors on the first word are reported right away
+/// assert_eq!(serial.read(&mut buffer), Err(ErrorKind::Overrun));
+///
+/// serial.write(b"ok").unwrap();
+/// serial.flush().unwrap();
+/// assert_eq!(serial.release().tx, b"ok");
+/// ```
+pub struct NbSerial<S: ErrorType> {
+    serial: S,
+    /// Error returned by the wrapped implementation after the first word of a `read`
+    pending: Option<S::Error>,
+}
+
+impl<S: ErrorType> NbSerial<S> {
+    /// Creates a new `NbSerial` wrapping an `nb::serial` implementation
+    pub fn new(serial: S) -> Self {
+        Self {
+            serial,
+            pending: None,
+        }
+    }
+
+    /// Destroys the adapter and returns the wrapped `nb::serial` implementation
+    pub fn release(self) -> S {
+        self.serial
+    }
+}
+
+impl<S: ErrorType> ErrorType for NbSerial<S> {
+    type Error = S::Error;
+}
+
+impl<Word, S> Read<Word> for NbSerial<S>
+where
+    S: nb_serial::Read<Word>,
+{
+    fn read_exact(&mut self, buffer: &mut [Word]) -> Result<(), Self::Error> {
+        if let Some(e) = self.pending.take() {
+            return Err(e);
+        }
+
+        for word in buffer.iter_mut() {
+            *word = nb::block!(self.serial.read())?;
+        }
+
+        Ok(())
+    }
+
+    fn read(&mut self, buffer: &mut [Word]) -> Result<usize, Self::Error> {
+        if let Some(e) = self.pending.take() {
+            return Err(e);
+        }
+
+        let (first, rest) = match buffer.split_first_mut() {
+            Some(split) => split,
+            None => return Ok(0),
+        };
+        *first = nb::block!(self.serial.read())?;
+
+        let mut count = 1;
+        for word in rest {
+            match self.serial.read() {
+                Ok(w) => *word = w,
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(e)) => {
+                    // report the error once the words already read are returned
+                    self.pending = Some(e);
+                    break;
+                }
+            }
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+impl<Word, S> Write<Word> for NbSerial<S>
+where
+    Word: Copy,
+    S: nb_serial::Write<Word>,
+{
+    fn write(&mut self, buffer: &[Word]) -> Result<(), Self::Error> {
+        for word in buffer {
+            nb::block!(self.serial.write(*word))?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        nb::block!(self.serial.flush())
+    }
+}
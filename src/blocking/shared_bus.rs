@@ -0,0 +1,568 @@
+//! Sharing a bus between several drivers
+//!
+//! The blocking I2C and SPI traits take `&mut self`, so only one driver can own a bus at a time.
+//! A [`BusManager`] takes ownership of a bus and hands out proxies which implement the same
+//! traits, so that several drivers can use the bus through their own proxy.
+//!
+//! Each call on a proxy locks the bus for its whole duration, which guarantees that operations
+//! issued through different proxies never interleave. The locking mechanism is defined by the
+//! [`BusMutex`] implementation used by the manager:
+//!
+//! - [`RefCell`] for sharing a bus within a single execution context (see [`RefCellBusManager`]),
+//! - [`CriticalSectionMutex`] for sharing a bus between execution contexts, e.g. between the
+//!   main loop and interrupt handlers (requires the `critical-section` feature),
+//! - any other lock, e.g. an RTOS mutex, by implementing [`BusMutex`] for it.
+//!
+//! [`BusManager`]: struct.BusManager.html
+//! [`BusMutex`]: trait.BusMutex.html
+//! [`RefCell`]: https://doc.rust-lang.org/core/cell/struct.RefCell.html
+//! [`RefCellBusManager`]: type.RefCellBusManager.html
+//! [`CriticalSectionMutex`]: struct.CriticalSectionMutex.html
+//!
+//! # Examples
+//!
+//! Two drivers sharing an I2C bus, and two devices sharing an SPI bus. Every SPI transaction is
+//! executed with the bus locked, from the assertion of the chip select line of the device until
+//! its deassertion.
+//!
+//! ```
+//! use embedded_hal::blocking::digital::{self, OutputPin};
+//! use embedded_hal::blocking::i2c::{self, WriteRead};
+//! use embedded_hal::blocking::shared_bus::RefCellBusManager;
+//! use embedded_hal::blocking::spi::{self, Operation, SpiBus, SpiDevice, Transactional, Transfer, Write};
+//! use core::cell::RefCell;
+//! use core::convert::Infallible;
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Event {
+//!     I2c(u8),
+//!     CsLow(u8),
+//!     Spi(u8),
+//!     CsHigh(u8),
+//! }
+//!
+//! /// A bus logging all the words written to it
+//! struct Bus<'a>(&'a RefCell<Vec<Event>>);
+//!
+//! impl i2c::ErrorType for Bus<'_> {
+//!     type Error = Infallible;
+//! }
+//!
+//! impl WriteRead for Bus<'_> {
+//!     fn write_read(&mut self, address: u8, _: &[u8], buffer: &mut [u8]) -> Result<(), Infallible> {
+//!         self.0.borrow_mut().push(Event::I2c(address));
+//!         buffer.iter_mut().for_each(|b| *b = address);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! impl spi::ErrorType for Bus<'_> {
+//!     type Error = Infallible;
+//! }
+//!
+//! impl Transfer<u8> for Bus<'_> {
+//!     fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Infallible> {
+//!         self.write(words)?;
+//!         Ok(words)
+//!     }
+//! }
+//!
+//! impl Write<u8> for Bus<'_> {
+//!     fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+//!         self.0.borrow_mut().extend(words.iter().map(|w| Event::Spi(*w)));
+//!         Ok(())
+//!     }
+//! }
+//!
+//! impl Transactional<u8> for Bus<'_> {
+//!     fn exec<'a>(&mut self, operations: &mut [Operation<'a, u8>]) -> Result<(), Infallible> {
+//!         for op in operations {
+//!             match op {
+//!                 Operation::Write(words) => self.write(words)?,
+//!                 Operation::Transfer(words) => self.transfer(words).map(|_| ())?,
+//!             }
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! impl SpiBus<u8> for Bus<'_> {
+//!     fn flush(&mut self) -> Result<(), Infallible> {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! /// A chip select pin logging its state changes
+//! struct Cs<'a>(u8, &'a RefCell<Vec<Event>>);
+//!
+//! impl digital::ErrorType for Cs<'_> {
+//!     type Error = Infallible;
+//! }
+//!
+//! impl OutputPin for Cs<'_> {
+//!     fn set_low(&mut self) -> Result<(), Infallible> {
+//!         self.1.borrow_mut().push(Event::CsLow(self.0));
+//!         Ok(())
+//!     }
+//!     fn set_high(&mut self) -> Result<(), Infallible> {
+//!         self.1.borrow_mut().push(Event::CsHigh(self.0));
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let log = RefCell::new(Vec::new());
+//!
+//! let i2c_bus = RefCellBusManager::new(Bus(&log));
+//! let mut sensor_a = i2c_bus.acquire_i2c();
+//! let mut sensor_b = i2c_bus.acquire_i2c();
+//!
+//! let mut buffer = [0; 2];
+//! sensor_a.write_read(0x10, &[0x00], &mut buffer).unwrap();
+//! assert_eq!(buffer, [0x10; 2]);
+//! sensor_b.write_read(0x20, &[0x00], &mut buffer).unwrap();
+//! assert_eq!(buffer, [0x20; 2]);
+//!
+//! let spi_bus = RefCellBusManager::new(Bus(&log));
+//! let mut device_a = spi_bus.acquire_spi(Cs(1, &log));
+//! let mut device_b = spi_bus.acquire_spi(Cs(2, &log));
+//!
+//! device_a.transaction(|bus| {
+//!     bus.write(&[0xA0])?;
+//!     bus.write(&[0xA1])
+//! }).unwrap();
+//! device_b.write(&[0xB0, 0xB1]).unwrap();
+//! device_a.exec(&mut [Operation::Write(&[0xA2])]).unwrap();
+//!
+//! assert_eq!(
+//!     *log.borrow(),
+//!     [
+//!         Event::I2c(0x10),
+//!         Event::I2c(0x20),
+//!         Event::CsLow(1),
+//!         Event::Spi(0xA0),
+//!         Event::Spi(0xA1),
+//!         Event::CsHigh(1),
+//!         Event::CsLow(2),
+//!         Event::Spi(0xB0),
+//!         Event::Spi(0xB1),
+//!         Event::CsHigh(2),
+//!         Event::CsLow(1),
+//!         Event::Spi(0xA2),
+//!         Event::CsHigh(1),
+//!     ]
+//! );
+//! ```
+
+use core::cell::RefCell;
+
+use crate::blocking::digital::OutputPin;
+use crate::blocking::i2c::{self, AddressMode};
+use crate::blocking::spi::{self, DeviceError, SpiBus, SpiDevice};
+
+/// Common interface for the locks used to share a bus
+///
+/// # Examples
+///
+/// Implementing `BusMutex` for a lock provided by an RTOS:
+///
+/// ```
+/// use embedded_hal::blocking::shared_bus::{BusManager, BusMutex};
+///
+/// # mod rtos {
+/// #     pub struct Mutex<T>(core::cell::RefCell<T>);
+/// #     impl<T> Mutex<T> {
+/// #         pub fn new(value: T) -> Self { Mutex(core::cell::RefCell::new(value)) }
+/// #         pub fn lock(&self) -> core::cell::RefMut<'_, T> { self.0.borrow_mut() }
+/// #     }
+/// # }
+/// /// Wrapper around the RTOS mutex, as `BusMutex` cannot be implemented for it directly
+/// pub struct RtosMutex<T>(rtos::Mutex<T>);
+///
+/// impl<T> BusMutex for RtosMutex<T> {
+///     type Bus = T;
+///
+///     fn create(bus: T) -> Self {
+///         RtosMutex(rtos::Mutex::new(bus))
+///     }
+///
+///     fn lock<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+///         let mut guard = self.0.lock();
+///         f(&mut guard)
+///     }
+/// }
+///
+/// pub type RtosBusManager<BUS> = BusManager<RtosMutex<BUS>>;
+/// ```
+pub trait BusMutex {
+    /// The bus protected by this lock
+    type Bus;
+
+    /// Creates a new lock protecting `bus`
+    fn create(bus: Self::Bus) -> Self;
+
+    /// Locks the bus and calls `f` with exclusive access to it
+    ///
+    /// The bus must remain locked until `f` returns.
+    fn lock<R, F: FnOnce(&mut Self::Bus) -> R>(&self, f: F) -> R;
+}
+
+/// Lock for sharing a bus within a single execution context
+///
+/// # Panics
+///
+/// Locking the bus while it is already locked, e.g. by using a proxy from within a transaction
+/// of another proxy of the same bus, panics.
+impl<T> BusMutex for RefCell<T> {
+    type Bus = T;
+
+    fn create(bus: T) -> Self {
+        RefCell::new(bus)
+    }
+
+    fn lock<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        f(&mut self.borrow_mut())
+    }
+}
+
+/// Lock for sharing a bus between execution contexts, based on critical sections
+///
+/// The bus is locked by running the whole operation within a critical section, see the
+/// [`critical-section`](https://crates.io/crates/critical-section) crate.
+///
+/// *This type is available if embedded-hal is built with the `"critical-section"` feature.*
+///
+/// # Examples
+///
+/// Two threads using different I2C proxies of a shared bus. The operations executed through
+/// the proxies never interleave.
+///
+/// ```
+/// use embedded_hal::blocking::i2c::{self, Operation, Transactional};
+/// use embedded_hal::blocking::shared_bus::CriticalSectionBusManager;
+/// use core::convert::Infallible;
+/// use std::thread;
+///
+/// /// A bus logging the addresses of the operations executed on it
+/// struct Bus(Vec<u8>);
+///
+/// impl i2c::ErrorType for Bus {
+///     type Error = Infallible;
+/// }
+///
+/// impl Transactional for Bus {
+///     fn exec<'a>(&mut self, address: u8, operations: &mut [Operation<'a>]) -> Result<(), Infallible> {
+///         for _ in operations.iter() {
+///             self.0.push(address);
+///             // give the other thread an opportunity to run
+///             thread::yield_now();
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let bus: &'static _ = Box::leak(Box::new(CriticalSectionBusManager::new(Bus(Vec::new()))));
+///
+/// let threads: Vec<_> = [0x10, 0x20]
+///     .iter()
+///     .map(|&address| {
+///         let mut proxy = bus.acquire_i2c();
+///         thread::spawn(move || {
+///             for _ in 0..100 {
+///                 let mut operations = [
+///                     Operation::Write(&[0]),
+///                     Operation::Write(&[1]),
+///                     Operation::Write(&[2]),
+///                     Operation::Write(&[3]),
+///                 ];
+///                 proxy.exec(address, &mut operations).unwrap();
+///             }
+///         })
+///     })
+///     .collect();
+/// threads.into_iter().for_each(|t| t.join().unwrap());
+///
+/// let log = bus.lock(|bus| bus.0.clone());
+/// assert_eq!(log.len(), 800);
+/// for transaction in log.chunks(4) {
+///     assert!(transaction.iter().all(|&address| address == transaction[0]));
+/// }
+/// ```
+#[cfg(feature = "critical-section")]
+pub struct CriticalSectionMutex<T> {
+    bus: critical_section::Mutex<RefCell<T>>,
+}
+
+#[cfg(feature = "critical-section")]
+impl<T> BusMutex for CriticalSectionMutex<T> {
+    type Bus = T;
+
+    fn create(bus: T) -> Self {
+        CriticalSectionMutex {
+            bus: critical_section::Mutex::new(RefCell::new(bus)),
+        }
+    }
+
+    fn lock<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> R {
+        critical_section::with(|cs| f(&mut self.bus.borrow(cs).borrow_mut()))
+    }
+}
+
+/// Owner of a shared bus, handing out proxies to it
+pub struct BusManager<M> {
+    mutex: M,
+}
+
+/// Manager for a bus shared within a single execution context
+pub type RefCellBusManager<BUS> = BusManager<RefCell<BUS>>;
+
+/// Manager for a bus shared between execution contexts, based on critical sections
+///
+/// *This type is available if embedded-hal is built with the `"critical-section"` feature.*
+#[cfg(feature = "critical-section")]
+pub type CriticalSectionBusManager<BUS> = BusManager<CriticalSectionMutex<BUS>>;
+
+impl<M: BusMutex> BusManager<M> {
+    /// Creates a new manager taking ownership of `bus`
+    pub fn new(bus: M::Bus) -> Self {
+        BusManager {
+            mutex: M::create(bus),
+        }
+    }
+
+    /// Locks the bus and calls `f` with exclusive access to it
+    ///
+    /// This can be used to access the bus directly, e.g. to reconfigure it.
+    pub fn lock<R, F: FnOnce(&mut M::Bus) -> R>(&self, f: F) -> R {
+        self.mutex.lock(f)
+    }
+
+    /// Returns a new I2C proxy to the bus
+    pub fn acquire_i2c(&self) -> I2cProxy<'_, M> {
+        I2cProxy { mutex: &self.mutex }
+    }
+
+    /// Returns a new SPI proxy to the bus, for the device selected by `cs`
+    ///
+    /// The CS pin should be deasserted (driven high) before acquiring the proxy.
+    pub fn acquire_spi<CS: OutputPin>(&self, cs: CS) -> SpiProxy<'_, M, CS> {
+        SpiProxy {
+            mutex: &self.mutex,
+            cs,
+        }
+    }
+}
+
+/// Proxy to a shared I2C bus
+///
+/// This type implements the same blocking I2C traits as the shared bus, locking the bus for
+/// the duration of every call.
+pub struct I2cProxy<'a, M> {
+    mutex: &'a M,
+}
+
+impl<M> i2c::ErrorType for I2cProxy<'_, M>
+where
+    M: BusMutex,
+    M::Bus: i2c::ErrorType,
+{
+    type Error = <M::Bus as i2c::ErrorType>::Error;
+}
+
+impl<A, M> i2c::Read<A> for I2cProxy<'_, M>
+where
+    A: AddressMode,
+    M: BusMutex,
+    M::Bus: i2c::Read<A>,
+{
+    fn read(&mut self, address: A, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.mutex.lock(|bus| bus.read(address, buffer))
+    }
+}
+
+impl<A, M> i2c::Write<A> for I2cProxy<'_, M>
+where
+    A: AddressMode,
+    M: BusMutex,
+    M::Bus: i2c::Write<A>,
+{
+    fn write(&mut self, address: A, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.mutex.lock(|bus| bus.write(address, bytes))
+    }
+}
+
+impl<A, M> i2c::WriteIter<A> for I2cProxy<'_, M>
+where
+    A: AddressMode,
+    M: BusMutex,
+    M::Bus: i2c::WriteIter<A>,
+{
+    fn write_iter<B>(&mut self, address: A, bytes: B) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.mutex.lock(|bus| bus.write_iter(address, bytes))
+    }
+}
+
+impl<A, M> i2c::WriteRead<A> for I2cProxy<'_, M>
+where
+    A: AddressMode,
+    M: BusMutex,
+    M::Bus: i2c::WriteRead<A>,
+{
+    fn write_read(
+        &mut self,
+        address: A,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.mutex
+            .lock(|bus| bus.write_read(address, bytes, buffer))
+    }
+}
+
+impl<A, M> i2c::WriteIterRead<A> for I2cProxy<'_, M>
+where
+    A: AddressMode,
+    M: BusMutex,
+    M::Bus: i2c::WriteIterRead<A>,
+{
+    fn write_iter_read<B>(
+        &mut self,
+        address: A,
+        bytes: B,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>
+    where
+        B: IntoIterator<Item = u8>,
+    {
+        self.mutex
+            .lock(|bus| bus.write_iter_read(address, bytes, buffer))
+    }
+}
+
+impl<A, M> i2c::Transactional<A> for I2cProxy<'_, M>
+where
+    A: AddressMode,
+    M: BusMutex,
+    M::Bus: i2c::Transactional<A>,
+{
+    fn exec<'a>(
+        &mut self,
+        address: A,
+        operations: &mut [i2c::Operation<'a>],
+    ) -> Result<(), Self::Error> {
+        self.mutex.lock(|bus| bus.exec(address, operations))
+    }
+}
+
+impl<A, M> i2c::TransactionalIter<A> for I2cProxy<'_, M>
+where
+    A: AddressMode,
+    M: BusMutex,
+    M::Bus: i2c::TransactionalIter<A>,
+{
+    fn exec_iter<'a, O>(&mut self, address: A, operations: O) -> Result<(), Self::Error>
+    where
+        O: IntoIterator<Item = i2c::Operation<'a>>,
+    {
+        self.mutex.lock(|bus| bus.exec_iter(address, operations))
+    }
+}
+
+/// Proxy to a shared SPI bus, for a single device on it
+///
+/// This type implements [`SpiDevice`](../spi/trait.SpiDevice.html): the bus is locked for the
+/// whole duration of a transaction, during which the chip select line of the device is
+/// asserted. The `Transfer`, `Write` and `WriteIter` traits are implemented as well, running
+/// every call in its own transaction.
+pub struct SpiProxy<'a, M, CS> {
+    mutex: &'a M,
+    cs: CS,
+}
+
+impl<M, CS> SpiProxy<'_, M, CS> {
+    /// Destroys the proxy and returns the CS pin of the device
+    pub fn release(self) -> CS {
+        self.cs
+    }
+}
+
+impl<M, CS> spi::ErrorType for SpiProxy<'_, M, CS>
+where
+    M: BusMutex,
+    M::Bus: spi::ErrorType,
+    CS: OutputPin,
+{
+    type Error = DeviceError<<M::Bus as spi::ErrorType>::Error, CS::Error>;
+}
+
+impl<W, M, CS> SpiDevice<W> for SpiProxy<'_, M, CS>
+where
+    W: 'static,
+    M: BusMutex,
+    M::Bus: SpiBus<W>,
+    CS: OutputPin,
+{
+    type Bus = M::Bus;
+
+    fn transaction<R, F>(&mut self, f: F) -> Result<R, Self::Error>
+    where
+        F: FnOnce(&mut Self::Bus) -> Result<R, <Self::Bus as spi::ErrorType>::Error>,
+    {
+        let cs = &mut self.cs;
+        self.mutex.lock(|bus| spi::transaction(bus, cs, f))
+    }
+}
+
+impl<W, M, CS> spi::Transfer<W> for SpiProxy<'_, M, CS>
+where
+    W: 'static,
+    M: BusMutex,
+    M::Bus: SpiBus<W>,
+    CS: OutputPin,
+{
+    fn transfer<'w>(&mut self, words: &'w mut [W]) -> Result<&'w [W], Self::Error> {
+        self.transaction(|bus| bus.transfer(&mut *words).map(|_| ()))?;
+        Ok(words)
+    }
+}
+
+impl<W, M, CS> spi::Write<W> for SpiProxy<'_, M, CS>
+where
+    W: 'static,
+    M: BusMutex,
+    M::Bus: SpiBus<W>,
+    CS: OutputPin,
+{
+    fn write(&mut self, words: &[W]) -> Result<(), Self::Error> {
+        self.transaction(|bus| bus.write(words))
+    }
+}
+
+impl<W, M, CS> spi::WriteIter<W> for SpiProxy<'_, M, CS>
+where
+    W: 'static,
+    M: BusMutex,
+    M::Bus: SpiBus<W> + spi::WriteIter<W>,
+    CS: OutputPin,
+{
+    fn write_iter<WI>(&mut self, words: WI) -> Result<(), Self::Error>
+    where
+        WI: IntoIterator<Item = W>,
+    {
+        self.transaction(|bus| bus.write_iter(words))
+    }
+}
+
+impl<W, M, CS> spi::Transactional<W> for SpiProxy<'_, M, CS>
+where
+    W: 'static,
+    M: BusMutex,
+    M::Bus: SpiBus<W>,
+    CS: OutputPin,
+{
+    fn exec<'a>(&mut self, operations: &mut [spi::Operation<'a, W>]) -> Result<(), Self::Error> {
+        self.transaction(|bus| bus.exec(operations))
+    }
+}
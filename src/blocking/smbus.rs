@@ -0,0 +1,591 @@
+//! Blocking SMBus API
+//!
+//! The System Management Bus is a protocol layer on top of I2C, used by battery gauges, power
+//! supplies (PMBus) and many other devices. The [`Smbus`](trait.Smbus.html) trait provides the
+//! SMBus commands for any I2C bus implementing the blocking [`Write`], [`WriteRead`] and
+//! [`Transactional`] traits.
+//!
+//! Every command except Quick Command can use Packet Error Checking (PEC): the last byte of the
+//! transfer is then the CRC-8 of all the bytes sent on the bus during the command, addresses
+//! included, see [`pec`](fn.pec.html). A wrong PEC byte received from a device is reported as an
+//! [`ErrorKind::PecMismatch`](enum.ErrorKind.html#variant.PecMismatch) error.
+//!
+//! Words are sent and received least significant byte first. Blocks are up to 255 bytes long, as
+//! allowed by version 3 of the SMBus specification; devices following older versions of the
+//! specification accept blocks of up to 32 bytes.
+//!
+//! [`Write`]: ../i2c/trait.Write.html
+//! [`WriteRead`]: ../i2c/trait.WriteRead.html
+//! [`Transactional`]: ../i2c/trait.Transactional.html
+
+use crate::blocking::i2c::{self, Operation, SevenBitAddress, Transactional, Write, WriteRead};
+
+/// SMBus error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic SMBus error kind
+    ///
+    /// By using this method, SMBus errors can be converted to a set of generic
+    /// SMBus errors upon which generic code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// SMBus error kind
+///
+/// This represents a common set of SMBus operation errors.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The underlying I2C bus failed.
+    I2c(i2c::ErrorKind),
+    /// The PEC byte received does not match the bytes sent on the bus.
+    PecMismatch,
+    /// A block or block buffer is longer than 255 bytes, or the device sent a block longer than
+    /// the buffer receiving it.
+    BlockLength,
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::I2c(kind) => kind.fmt(f),
+            Self::PecMismatch => write!(f, "The PEC byte received does not match the data"),
+            Self::BlockLength => write!(f, "The block is too long"),
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+/// Error of the [`Smbus`](trait.Smbus.html) commands
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CommandError<E> {
+    /// The I2C implementation failed
+    I2c(E),
+    /// The PEC byte received does not match the bytes sent on the bus
+    PecMismatch,
+    /// A block or block buffer is longer than 255 bytes, or the device sent a block longer than
+    /// the buffer receiving it
+    BlockLength,
+}
+
+impl<E: i2c::Error> Error for CommandError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::I2c(e) => ErrorKind::I2c(e.kind()),
+            Self::PecMismatch => ErrorKind::PecMismatch,
+            Self::BlockLength => ErrorKind::BlockLength,
+        }
+    }
+}
+
+/// Computes the SMBus PEC (CRC-8, polynomial `x^8 + x^2 + x + 1`) of `data`
+///
+/// The PEC of a command covers every byte sent on the bus, including the address bytes, i.e. the
+/// 7-bit address shifted left by one and followed by the read/write bit.
+///
+/// ```
+/// use embedded_hal::blocking::smbus::pec;
+///
+/// assert_eq!(pec(b"123456789"), 0xF4);
+///
+/// // Write Byte of 0x55 to the command 0x01 of the device at address 0x5A
+/// assert_eq!(pec(&[0x5A << 1, 0x01, 0x55]), 0xF8);
+/// ```
+pub fn pec(data: &[u8]) -> u8 {
+    crc8(0, data)
+}
+
+fn crc8(mut crc: u8, data: &[u8]) -> u8 {
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Largest block allowed by the SMBus specification
+const MAX_BLOCK_LENGTH: usize = 255;
+
+/// SMBus commands
+///
+/// This trait is implemented for any I2C bus implementing the blocking [`Write`], [`WriteRead`]
+/// and [`Transactional`] traits with 7-bit addresses. The `pec` argument of the commands enables
+/// Packet Error Checking.
+///
+/// [`Write`]: ../i2c/trait.Write.html
+/// [`WriteRead`]: ../i2c/trait.WriteRead.html
+/// [`Transactional`]: ../i2c/trait.Transactional.html
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::i2c::{self, ErrorType, NoAcknowledgeSource, Operation, Transactional};
+/// use embedded_hal::blocking::smbus::{pec, Error, ErrorKind, Smbus};
+///
+/// /// A smart battery, answering at address 0x0B with PEC
+/// struct Battery {
+///     /// Bytes written by the last command
+///     written: Vec<u8>,
+///     /// Whether to send a wrong PEC byte
+///     corrupt: bool,
+/// }
+///
+/// impl Battery {
+///     fn response(&self) -> Vec<u8> {
+///         match self.written.first() {
+///             // Voltage(): 11792 mV
+///             Some(0x09) => vec![0x10, 0x2E],
+///             // DeviceChemistry(): block of 4 bytes
+///             Some(0x22) => b"\x04LION".to_vec(),
+///             _ => vec![],
+///         }
+///     }
+/// }
+///
+/// impl ErrorType for Battery {
+///     type Error = i2c::ErrorKind;
+/// }
+///
+/// impl Transactional for Battery {
+///     fn exec(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+///         if address != 0x0B {
+///             return Err(i2c::ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+///         }
+///
+///         // bytes sent on the bus, covered by the PEC
+///         let mut bus = Vec::new();
+///         let mut response = Vec::new();
+///         let mut position = 0;
+///         self.written.clear();
+///         for (i, operation) in operations.iter_mut().enumerate() {
+///             match operation {
+///                 Operation::Write(bytes) => {
+///                     if i == 0 {
+///                         bus.push(address << 1);
+///                     }
+///                     bus.extend_from_slice(bytes);
+///                     self.written.extend_from_slice(bytes);
+///                 }
+///                 Operation::Read(buffer) => {
+///                     if position == 0 {
+///                         bus.push(address << 1 | 1);
+///                         response = self.response();
+///                     }
+///                     for byte in buffer.iter_mut() {
+///                         *byte = if position < response.len() {
+///                             bus.push(response[position]);
+///                             response[position]
+///                         } else if position == response.len() {
+///                             pec(&bus) ^ self.corrupt as u8
+///                         } else {
+///                             // the bus is idle
+///                             0xFF
+///                         };
+///                         position += 1;
+///                     }
+///                 }
+///             }
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// impl i2c::Write for Battery {
+///     fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+///         self.exec(address, &mut [Operation::Write(bytes)])
+///     }
+/// }
+///
+/// impl i2c::WriteRead for Battery {
+///     fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+///         self.exec(address, &mut [Operation::Write(bytes), Operation::Read(buffer)])
+///     }
+/// }
+///
+/// let mut battery = Battery { written: Vec::new(), corrupt: false };
+///
+/// assert_eq!(battery.read_word(0x0B, 0x09, true), Ok(11_792));
+///
+/// // the battery sends a block of 4 bytes
+/// let mut chemistry = [0; 4];
+/// assert_eq!(battery.block_read(0x0B, 0x22, &mut chemistry, true), Ok(4));
+/// assert_eq!(&chemistry, b"LION");
+///
+/// // ManufacturerAccess(0x0001), followed by the PEC
+/// battery.write_word(0x0B, 0x00, 0x0001, true).unwrap();
+/// assert_eq!(battery.written, [0x00, 0x01, 0x00, pec(&[0x16, 0x00, 0x01, 0x00])]);
+///
+/// // a corrupted response is detected
+/// battery.corrupt = true;
+/// let error = battery.read_word(0x0B, 0x09, true).unwrap_err();
+/// assert_eq!(error.kind(), ErrorKind::PecMismatch);
+///
+/// // the error kinds of the I2C bus are available too
+/// let error = battery.quick_command(0x0C, false).unwrap_err();
+/// assert_eq!(
+///     error.kind(),
+///     ErrorKind::I2c(i2c::ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+/// );
+/// ```
+pub trait Smbus:
+    Write<SevenBitAddress> + WriteRead<SevenBitAddress> + Transactional<SevenBitAddress>
+{
+    /// Quick Command: sends the read/write bit of the address as the only data
+    ///
+    /// ``` text
+    /// Master: ST SAD+R/W     SP
+    /// Slave:             SAK
+    /// ```
+    ///
+    /// With the read bit, this is an empty `Operation::Read`. The target may already drive the
+    /// first bit of a byte once it acknowledged its address, so the I2C implementation must
+    /// support empty reads, e.g. by reading a byte without acknowledging it before the stop
+    /// condition, as [`BitBangI2c`](../../bitbang/i2c/struct.BitBangI2c.html) does. Some I2C
+    /// peripherals cannot send empty reads.
+    fn quick_command(&mut self, address: u8, read: bool) -> Result<(), CommandError<Self::Error>> {
+        let operation = if read {
+            Operation::Read(&mut [])
+        } else {
+            Operation::Write(&[])
+        };
+        self.exec(address, &mut [operation])
+            .map_err(CommandError::I2c)
+    }
+
+    /// Send Byte: writes `byte`, without command code
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     B     [PEC]     SP
+    /// Slave:           SAK   SAK       SAK
+    /// ```
+    fn send_byte(
+        &mut self,
+        address: u8,
+        byte: u8,
+        pec: bool,
+    ) -> Result<(), CommandError<Self::Error>> {
+        write(self, address, &[byte], pec)
+    }
+
+    /// Receive Byte: reads a byte, without command code
+    ///
+    /// ``` text
+    /// Master: ST SAD+R        [MAK     ]NMAK SP
+    /// Slave:           SAK B    [  PEC]
+    /// ```
+    fn receive_byte(&mut self, address: u8, pec: bool) -> Result<u8, CommandError<Self::Error>> {
+        let mut buffer = [0; 2];
+        let length = if pec { 2 } else { 1 };
+        self.exec(address, &mut [Operation::Read(&mut buffer[..length])])
+            .map_err(CommandError::I2c)?;
+        if pec && crc8(0, &[address << 1 | 1, buffer[0], buffer[1]]) != 0 {
+            return Err(CommandError::PecMismatch);
+        }
+
+        Ok(buffer[0])
+    }
+
+    /// Write Byte: writes `byte` to the `command` code
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     CMD     B     [PEC]     SP
+    /// Slave:           SAK     SAK   SAK       SAK
+    /// ```
+    fn write_byte(
+        &mut self,
+        address: u8,
+        command: u8,
+        byte: u8,
+        pec: bool,
+    ) -> Result<(), CommandError<Self::Error>> {
+        write(self, address, &[command, byte], pec)
+    }
+
+    /// Write Word: writes `word` to the `command` code
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     CMD     LOW     HIGH     [PEC]     SP
+    /// Slave:           SAK     SAK     SAK      SAK       SAK
+    /// ```
+    fn write_word(
+        &mut self,
+        address: u8,
+        command: u8,
+        word: u16,
+        pec: bool,
+    ) -> Result<(), CommandError<Self::Error>> {
+        let [low, high] = word.to_le_bytes();
+        write(self, address, &[command, low, high], pec)
+    }
+
+    /// Read Byte: reads a byte from the `command` code
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     CMD     SR SAD+R        [MAK     ]NMAK SP
+    /// Slave:           SAK     SAK          SAK B    [  PEC]
+    /// ```
+    fn read_byte(
+        &mut self,
+        address: u8,
+        command: u8,
+        pec: bool,
+    ) -> Result<u8, CommandError<Self::Error>> {
+        let mut buffer = [0];
+        write_read(self, address, &[command], &mut buffer, pec)?;
+        Ok(buffer[0])
+    }
+
+    /// Read Word: reads a word from the `command` code
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     CMD     SR SAD+R         MAK      [MAK     ]NMAK SP
+    /// Slave:           SAK     SAK          SAK LOW     HIGH    [  PEC]
+    /// ```
+    fn read_word(
+        &mut self,
+        address: u8,
+        command: u8,
+        pec: bool,
+    ) -> Result<u16, CommandError<Self::Error>> {
+        let mut buffer = [0; 2];
+        write_read(self, address, &[command], &mut buffer, pec)?;
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    /// Process Call: writes `word` to the `command` code and reads a word back
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     CMD     LOW     HIGH     SR SAD+R         MAK      [MAK     ]NMAK SP
+    /// Slave:           SAK     SAK     SAK      SAK          SAK LOW     HIGH    [  PEC]
+    /// ```
+    fn process_call(
+        &mut self,
+        address: u8,
+        command: u8,
+        word: u16,
+        pec: bool,
+    ) -> Result<u16, CommandError<Self::Error>> {
+        let [low, high] = word.to_le_bytes();
+        let mut buffer = [0; 2];
+        write_read(self, address, &[command, low, high], &mut buffer, pec)?;
+        Ok(u16::from_le_bytes(buffer))
+    }
+
+    /// Block Write: writes the byte count of `bytes` followed by `bytes` to the `command` code
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     CMD     N     B1     ... BN     [PEC]     SP
+    /// Slave:           SAK     SAK   SAK    SAK ...    SAK       SAK
+    /// ```
+    ///
+    /// Returns a `BlockLength` error if `bytes` is longer than 255 bytes.
+    fn block_write(
+        &mut self,
+        address: u8,
+        command: u8,
+        bytes: &[u8],
+        pec: bool,
+    ) -> Result<(), CommandError<Self::Error>> {
+        if bytes.len() > MAX_BLOCK_LENGTH {
+            return Err(CommandError::BlockLength);
+        }
+
+        let header = [command, bytes.len() as u8];
+        let crc = crc8(crc8(crc8(0, &[address << 1]), &header), bytes);
+        let checksum = [crc];
+        let mut operations = [
+            Operation::Write(&header),
+            Operation::Write(bytes),
+            Operation::Write(&checksum),
+        ];
+        let count = if pec { 3 } else { 2 };
+        self.exec(address, &mut operations[..count])
+            .map_err(CommandError::I2c)
+    }
+
+    /// Block Read of a block of known length: reads a block from the `command` code into
+    /// `buffer`, returning its byte count
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     CMD     SR SAD+R        MAK    MAK ...    [MAK     ]NMAK SP
+    /// Slave:           SAK     SAK          SAK N      B1     ... BN    [  PEC]
+    /// ```
+    ///
+    /// The I2C traits cannot adapt the length of a read to the byte count sent by the device:
+    /// exactly `buffer.len()` bytes are read after the byte count, so `buffer` must have the
+    /// length of the block sent by the device for this command. This is not a general Block
+    /// Read: if the device ends the block early, the bytes which follow it are read too, which
+    /// some devices report as a protocol error (e.g. a communication fault in the STATUS_CML
+    /// register of PMBus devices). These bytes are ignored, and the content of `buffer` after the
+    /// block is unspecified.
+    ///
+    /// Returns a `BlockLength` error if the block is longer than `buffer`, or if `buffer` is
+    /// longer than 255 bytes.
+    fn block_read(
+        &mut self,
+        address: u8,
+        command: u8,
+        buffer: &mut [u8],
+        pec: bool,
+    ) -> Result<usize, CommandError<Self::Error>> {
+        block(self, address, &[command], &[], buffer, pec)
+    }
+
+    /// Block Write-Block Read Process Call: writes a block to the `command` code and reads a
+    /// block back into `buffer`, returning its byte count
+    ///
+    /// ``` text
+    /// Master: ST SAD+W     CMD     M     O1     ... OM     SR SAD+R        MAK    MAK ...    [MAK     ]NMAK SP
+    /// Slave:           SAK     SAK   SAK    SAK ...    SAK          SAK N      I1     ... IN    [  PEC]
+    /// ```
+    ///
+    /// The block read back is received as described for [`block_read`](#method.block_read):
+    /// `buffer` must have the length of the block sent back by the device. Returns a
+    /// `BlockLength` error if `bytes` or `buffer` is longer than 255 bytes, or if the block read
+    /// back is longer than `buffer`.
+    fn block_process_call(
+        &mut self,
+        address: u8,
+        command: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+        pec: bool,
+    ) -> Result<usize, CommandError<Self::Error>> {
+        if bytes.len() > MAX_BLOCK_LENGTH {
+            return Err(CommandError::BlockLength);
+        }
+
+        block(
+            self,
+            address,
+            &[command, bytes.len() as u8],
+            bytes,
+            buffer,
+            pec,
+        )
+    }
+}
+
+impl<T> Smbus for T where
+    T: Write<SevenBitAddress> + WriteRead<SevenBitAddress> + Transactional<SevenBitAddress>
+{
+}
+
+/// Writes up to 3 `bytes`, followed by their PEC if enabled
+fn write<T: Smbus + ?Sized>(
+    i2c: &mut T,
+    address: u8,
+    bytes: &[u8],
+    pec: bool,
+) -> Result<(), CommandError<T::Error>> {
+    let mut buffer = [0; 4];
+    buffer[..bytes.len()].copy_from_slice(bytes);
+    let mut length = bytes.len();
+    if pec {
+        buffer[length] = crc8(crc8(0, &[address << 1]), bytes);
+        length += 1;
+    }
+
+    i2c.write(address, &buffer[..length])
+        .map_err(CommandError::I2c)
+}
+
+/// Writes up to 3 `bytes` and reads `buffer` (up to 2 bytes), followed by their PEC if enabled
+fn write_read<T: Smbus + ?Sized>(
+    i2c: &mut T,
+    address: u8,
+    bytes: &[u8],
+    buffer: &mut [u8],
+    pec: bool,
+) -> Result<(), CommandError<T::Error>> {
+    let mut received = [0; 3];
+    let length = if pec { buffer.len() + 1 } else { buffer.len() };
+    i2c.write_read(address, bytes, &mut received[..length])
+        .map_err(CommandError::I2c)?;
+
+    if pec {
+        let crc = crc8(crc8(0, &[address << 1]), bytes);
+        if crc8(crc8(crc, &[address << 1 | 1]), &received[..length]) != 0 {
+            return Err(CommandError::PecMismatch);
+        }
+    }
+
+    buffer.copy_from_slice(&received[..buffer.len()]);
+    Ok(())
+}
+
+/// Writes `header` and `bytes`, then reads a block of `buffer.len()` bytes into `buffer`
+fn block<T: Smbus + ?Sized>(
+    i2c: &mut T,
+    address: u8,
+    header: &[u8],
+    bytes: &[u8],
+    buffer: &mut [u8],
+    pec: bool,
+) -> Result<usize, CommandError<T::Error>> {
+    let capacity = buffer.len();
+    if capacity > MAX_BLOCK_LENGTH {
+        return Err(CommandError::BlockLength);
+    }
+
+    let mut count = [0];
+    let mut checksum = [0];
+    {
+        let mut operations = [
+            Operation::Write(header),
+            Operation::Write(bytes),
+            Operation::Read(&mut count),
+            Operation::Read(buffer),
+            Operation::Read(&mut checksum),
+        ];
+        let length = if pec { 5 } else { 4 };
+        i2c.exec(address, &mut operations[..length])
+            .map_err(CommandError::I2c)?;
+    }
+
+    let count = usize::from(count[0]);
+    if count > capacity {
+        return Err(CommandError::BlockLength);
+    }
+
+    if pec {
+        // the PEC follows the block, which may be shorter than the buffer
+        let received = if count < capacity {
+            buffer[count]
+        } else {
+            checksum[0]
+        };
+        let crc = crc8(crc8(crc8(0, &[address << 1]), header), bytes);
+        let crc = crc8(
+            crc8(crc, &[address << 1 | 1, count as u8]),
+            &buffer[..count],
+        );
+        if crc != received {
+            return Err(CommandError::PecMismatch);
+        }
+    }
+
+    Ok(count)
+}
@@ -1,28 +1,114 @@
 //! Blocking SPI API
+//!
+//! The `Transfer`, `Write`, `WriteIter` and `Transactional` traits model raw access to the bus
+//! and leave the management of chip select (CS) lines to their users. Drivers should instead
+//! depend on [`SpiDevice`], which runs operations within a transaction where the CS line of the
+//! device is asserted, and HALs should implement [`SpiBus`] for their SPI peripherals.
+//!
+//! [`ExclusiveDevice`] provides an [`SpiDevice`] implementation for a bus with a single device
+//! on it, given the bus and an [`OutputPin`] for its CS line.
+//!
+//! HALs which only implement [`FullDuplex`] can use [`FullDuplexSpi`] to obtain all the blocking
+//! SPI traits.
+//!
+//! [`SpiDevice`]: trait.SpiDevice.html
+//! [`SpiBus`]: trait.SpiBus.html
+//! [`ExclusiveDevice`]: struct.ExclusiveDevice.html
+//! [`OutputPin`]: ../digital/trait.OutputPin.html
+//! [`FullDuplex`]: ../../nb/spi/trait.FullDuplex.html
+//! [`FullDuplexSpi`]: struct.FullDuplexSpi.html
 
-/// Blocking transfer
-pub trait Transfer<W> {
+use crate::blocking::digital::OutputPin;
+use crate::nb::spi::FullDuplex;
+
+/// SPI error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic SPI error kind
+    ///
+    /// By using this method, SPI errors freely defined by HAL implementations
+    /// can be converted to a set of generic SPI errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// SPI error kind
+///
+/// This represents a common set of SPI operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common SPI errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The peripheral receive buffer was overrun
+    Overrun,
+    /// Multiple devices on the SPI bus are trying to drive the slave select pin, e.g. in a multi-master setup
+    ModeFault,
+    /// Received data does not conform to the peripheral configuration
+    FrameFormat,
+    /// An error occurred while asserting or deasserting the chip select pin
+    ChipSelectFault,
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overrun => write!(f, "The peripheral receive buffer was overrun"),
+            Self::ModeFault => write!(
+                f,
+                "Multiple devices on the SPI bus are trying to drive the slave select pin"
+            ),
+            Self::FrameFormat => write!(
+                f,
+                "Received data does not conform to the peripheral configuration"
+            ),
+            Self::ChipSelectFault => write!(
+                f,
+                "An error occurred while asserting or deasserting the chip select pin"
+            ),
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+/// SPI error type trait
+///
+/// This just defines the error type, to be used by the other SPI traits.
+pub trait ErrorType {
     /// Error type
-    type Error;
+    type Error: Error;
+}
 
+/// Blocking transfer
+pub trait Transfer<W>: ErrorType {
     /// Writes `words` to the slave. Returns the `words` received from the slave
     fn transfer<'w>(&mut self, words: &'w mut [W]) -> Result<&'w [W], Self::Error>;
 }
 
 /// Blocking write
-pub trait Write<W> {
-    /// Error type
-    type Error;
-
+pub trait Write<W>: ErrorType {
     /// Writes `words` to the slave, ignoring all the incoming words
     fn write(&mut self, words: &[W]) -> Result<(), Self::Error>;
 }
 
 /// Blocking write (iterator version)
-pub trait WriteIter<W> {
-    /// Error type
-    type Error;
-
+pub trait WriteIter<W>: ErrorType {
     /// Writes `words` to the slave, ignoring all the incoming words
     fn write_iter<WI>(&mut self, words: WI) -> Result<(), Self::Error>
     where
@@ -42,10 +128,469 @@ pub enum Operation<'a, W: 'static> {
 
 /// Transactional trait allows multiple actions to be executed
 /// as part of a single SPI transaction
-pub trait Transactional<W: 'static> {
-    /// Associated error type
-    type Error;
-
+pub trait Transactional<W: 'static>: ErrorType {
     /// Execute the provided transactions
     fn exec<'a>(&mut self, operations: &mut [Operation<'a, W>]) -> Result<(), Self::Error>;
 }
+
+/// SPI bus
+///
+/// `SpiBus` represents **exclusive ownership** over the whole SPI bus, with SCK, MOSI and MISO
+/// pins. It has no notion of chip select: operations are sent out on the bus as soon as they
+/// are issued. Drivers should depend on [`SpiDevice`](trait.SpiDevice.html) instead, which
+/// takes care of chip select.
+pub trait SpiBus<W: 'static>: Transfer<W> + Write<W> + Transactional<W> {
+    /// Waits until all operations have completed and the bus is idle
+    ///
+    /// Implementations are allowed to return from the other methods before the words have
+    /// actually been clocked out on the bus, e.g. when they are still in a FIFO.
+    fn flush(&mut self) -> Result<(), Self::Error>;
+}
+
+/// SPI device
+///
+/// `SpiDevice` represents ownership over a single SPI device on a (possibly shared) bus,
+/// selected with a chip select (CS) pin.
+///
+/// The `Transactional` implementation of an `SpiDevice` must execute all the given operations
+/// within a single [`transaction`](#tymethod.transaction).
+///
+/// # Examples
+///
+/// A driver reading a register of a device. The register address and the response are
+/// exchanged within a single transaction, i.e. with CS asserted during the whole exchange.
+///
+/// ```
+/// use embedded_hal::blocking::spi::{Operation, SpiDevice, Transactional, Transfer, Write};
+///
+/// const READ: u8 = 0x80;
+///
+/// pub fn read_register<D: SpiDevice<u8>>(device: &mut D, register: u8) -> Result<u8, D::Error> {
+///     let mut value = [0];
+///     device.exec(&mut [
+///         Operation::Write(&[READ | register]),
+///         Operation::Transfer(&mut value),
+///     ])?;
+///     Ok(value[0])
+/// }
+///
+/// pub fn read_two_registers<D: SpiDevice<u8>>(
+///     device: &mut D,
+///     register: u8,
+/// ) -> Result<[u8; 2], D::Error> {
+///     device.transaction(|bus| {
+///         let mut values = [0; 2];
+///         bus.write(&[READ | register])?;
+///         bus.transfer(&mut values)?;
+///         Ok(values)
+///     })
+/// }
+/// ```
+pub trait SpiDevice<W: 'static>: Transactional<W> {
+    /// SPI bus type
+    type Bus: SpiBus<W>;
+
+    /// Performs a transaction against the device
+    ///
+    /// - Asserts the CS (chip select) pin.
+    /// - Calls `f` with an exclusive reference to the bus, which can then be used to do
+    ///   transfers against the device.
+    /// - Waits for the bus operations to complete (see [`SpiBus::flush`]).
+    /// - Deasserts the CS pin.
+    ///
+    /// The CS pin must be deasserted even if `f` or waiting for the bus fails. The first
+    /// error that occurred is returned.
+    ///
+    /// On bus errors the implementation should try to deassert CS. If an error occurs while
+    /// deasserting CS the bus error should take priority as the return value.
+    ///
+    /// [`SpiBus::flush`]: trait.SpiBus.html#tymethod.flush
+    fn transaction<R, F>(&mut self, f: F) -> Result<R, Self::Error>
+    where
+        F: FnOnce(&mut Self::Bus) -> Result<R, <Self::Bus as ErrorType>::Error>;
+}
+
+/// Error type for the [`SpiDevice`](trait.SpiDevice.html) implementations of this crate
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DeviceError<BUS, CS> {
+    /// An inner SPI bus operation failed
+    Spi(BUS),
+    /// Asserting or deasserting CS failed
+    Cs(CS),
+}
+
+impl<BUS, CS> Error for DeviceError<BUS, CS>
+where
+    BUS: Error,
+    CS: core::fmt::Debug,
+{
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Spi(e) => e.kind(),
+            Self::Cs(_) => ErrorKind::ChipSelectFault,
+        }
+    }
+}
+
+/// Runs `f` on `bus` with `cs` asserted, deasserting `cs` even if `f` or flushing the bus fails
+pub(crate) fn transaction<W, BUS, CS, R, F>(
+    bus: &mut BUS,
+    cs: &mut CS,
+    f: F,
+) -> Result<R, DeviceError<BUS::Error, CS::Error>>
+where
+    W: 'static,
+    BUS: SpiBus<W>,
+    CS: OutputPin,
+    F: FnOnce(&mut BUS) -> Result<R, BUS::Error>,
+{
+    cs.set_low().map_err(DeviceError::Cs)?;
+
+    let result = f(bus);
+    let flush_result = bus.flush();
+    let cs_result = cs.set_high();
+
+    let value = result.map_err(DeviceError::Spi)?;
+    flush_result.map_err(DeviceError::Spi)?;
+    cs_result.map_err(DeviceError::Cs)?;
+
+    Ok(value)
+}
+
+/// [`SpiDevice`](trait.SpiDevice.html) implementation with exclusive access to the bus
+///
+/// This is the most straightforward way of obtaining an `SpiDevice` from an
+/// [`SpiBus`](trait.SpiBus.html), ideal for when only one device is present on the bus.
+///
+/// Besides `SpiDevice`, this type implements the `Transfer`, `Write` and `WriteIter` traits,
+/// running every call in its own transaction.
+///
+/// # Examples
+///
+/// The CS pin is released even if an operation fails:
+///
+/// ```
+/// use embedded_hal::blocking::digital::{self, OutputPin};
+/// use embedded_hal::blocking::spi::{
+///     self, DeviceError, ErrorKind, ExclusiveDevice, Operation, SpiBus, SpiDevice, Transactional,
+///     Transfer, Write,
+/// };
+/// use core::convert::Infallible;
+///
+/// /// A bus that fails to transfer words with the value `0xFF`
+/// struct Bus;
+///
+/// impl spi::ErrorType for Bus {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl Transfer<u8> for Bus {
+///     fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], ErrorKind> {
+///         self.write(words)?;
+///         Ok(words)
+///     }
+/// }
+///
+/// impl Write<u8> for Bus {
+///     fn write(&mut self, words: &[u8]) -> Result<(), ErrorKind> {
+///         match words.contains(&0xFF) {
+///             true => Err(ErrorKind::Overrun),
+///             false => Ok(()),
+///         }
+///     }
+/// }
+///
+/// impl Transactional<u8> for Bus {
+///     fn exec<'a>(&mut self, operations: &mut [Operation<'a, u8>]) -> Result<(), ErrorKind> {
+///         for op in operations {
+///             match op {
+///                 Operation::Write(words) => self.write(words)?,
+///                 Operation::Transfer(words) => self.transfer(words).map(|_| ())?,
+///             }
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// impl SpiBus<u8> for Bus {
+///     fn flush(&mut self) -> Result<(), ErrorKind> {
+///         Ok(())
+///     }
+/// }
+///
+/// /// A CS pin remembering whether it is asserted (low)
+/// struct Cs {
+///     asserted: bool,
+/// }
+///
+/// impl digital::ErrorType for Cs {
+///     type Error = Infallible;
+/// }
+///
+/// impl OutputPin for Cs {
+///     fn set_low(&mut self) -> Result<(), Self::Error> {
+///         self.asserted = true;
+///         Ok(())
+///     }
+///     fn set_high(&mut self) -> Result<(), Self::Error> {
+///         self.asserted = false;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut device = ExclusiveDevice::new(Bus, Cs { asserted: false });
+///
+/// let result = device.transaction(|bus| {
+///     bus.write(&[0x01, 0x02])?;
+///     Ok(())
+/// });
+/// assert_eq!(result, Ok(()));
+///
+/// let result = device.exec(&mut [Operation::Write(&[0x01]), Operation::Write(&[0xFF])]);
+/// assert_eq!(result, Err(DeviceError::Spi(ErrorKind::Overrun)));
+/// assert_eq!(spi::Error::kind(&result.unwrap_err()), ErrorKind::Overrun);
+///
+/// let (_bus, cs) = device.release();
+/// assert!(!cs.asserted);
+/// ```
+pub struct ExclusiveDevice<BUS, CS> {
+    bus: BUS,
+    cs: CS,
+}
+
+impl<BUS, CS> ExclusiveDevice<BUS, CS> {
+    /// Creates a new `ExclusiveDevice` from a bus and the CS pin of the device
+    pub fn new(bus: BUS, cs: CS) -> Self {
+        Self { bus, cs }
+    }
+
+    /// Destroys the device and returns the bus and the CS pin
+    pub fn release(self) -> (BUS, CS) {
+        (self.bus, self.cs)
+    }
+}
+
+impl<BUS, CS> ErrorType for ExclusiveDevice<BUS, CS>
+where
+    BUS: ErrorType,
+    CS: OutputPin,
+{
+    type Error = DeviceError<BUS::Error, CS::Error>;
+}
+
+impl<W, BUS, CS> SpiDevice<W> for ExclusiveDevice<BUS, CS>
+where
+    W: 'static,
+    BUS: SpiBus<W>,
+    CS: OutputPin,
+{
+    type Bus = BUS;
+
+    fn transaction<R, F>(&mut self, f: F) -> Result<R, Self::Error>
+    where
+        F: FnOnce(&mut Self::Bus) -> Result<R, BUS::Error>,
+    {
+        transaction(&mut self.bus, &mut self.cs, f)
+    }
+}
+
+impl<W, BUS, CS> Transfer<W> for ExclusiveDevice<BUS, CS>
+where
+    W: 'static,
+    BUS: SpiBus<W>,
+    CS: OutputPin,
+{
+    fn transfer<'w>(&mut self, words: &'w mut [W]) -> Result<&'w [W], Self::Error> {
+        self.transaction(|bus| bus.transfer(&mut *words).map(|_| ()))?;
+        Ok(words)
+    }
+}
+
+impl<W, BUS, CS> Write<W> for ExclusiveDevice<BUS, CS>
+where
+    W: 'static,
+    BUS: SpiBus<W>,
+    CS: OutputPin,
+{
+    fn write(&mut self, words: &[W]) -> Result<(), Self::Error> {
+        self.transaction(|bus| bus.write(words))
+    }
+}
+
+impl<W, BUS, CS> WriteIter<W> for ExclusiveDevice<BUS, CS>
+where
+    W: 'static,
+    BUS: SpiBus<W> + WriteIter<W>,
+    CS: OutputPin,
+{
+    fn write_iter<WI>(&mut self, words: WI) -> Result<(), Self::Error>
+    where
+        WI: IntoIterator<Item = W>,
+    {
+        self.transaction(|bus| bus.write_iter(words))
+    }
+}
+
+impl<W, BUS, CS> Transactional<W> for ExclusiveDevice<BUS, CS>
+where
+    W: 'static,
+    BUS: SpiBus<W>,
+    CS: OutputPin,
+{
+    fn exec<'a>(&mut self, operations: &mut [Operation<'a, W>]) -> Result<(), Self::Error> {
+        self.transaction(|bus| bus.exec(operations))
+    }
+}
+
+/// Blocking SPI traits implemented on top of [`FullDuplex`](../../nb/spi/trait.FullDuplex.html)
+///
+/// This type implements `Transfer`, `Write`, `WriteIter`, `Transactional` and `SpiBus` for any
+/// `FullDuplex` implementation. Every word written is followed by a read, as required by
+/// `FullDuplex`, so the words received while writing are read and discarded.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::blocking::spi::{
+///     ErrorType, FullDuplexSpi, Operation, Transactional, Transfer, Write,
+/// };
+/// use embedded_hal::nb::spi::{ErrorKind, FullDuplex};
+///
+/// /// A loopback SPI which reports an overrun if a word is not read before the next write
+/// struct Loopback {
+///     received: Option<u8>,
+///     written: Vec<u8>,
+/// }
+///
+/// impl ErrorType for Loopback {
+///     type Error = ErrorKind;
+/// }
+///
+/// impl FullDuplex<u8> for Loopback {
+///     fn read(&mut self) -> nb::Result<u8, ErrorKind> {
+///         // a word must have been written before reading
+///         Ok(self.received.take().expect("read without a preceding write"))
+///     }
+///
+///     fn write(&mut self, word: u8) -> nb::Result<(), ErrorKind> {
+///         if self.received.is_some() {
+///             return Err(nb::Error::Other(ErrorKind::Overrun));
+///         }
+///         self.written.push(word);
+///         self.received = Some(word.wrapping_add(1));
+///         Ok(())
+///     }
+/// }
+///
+/// let mut spi = FullDuplexSpi::new(Loopback { received: None, written: Vec::new() });
+///
+/// spi.write(&[0x01, 0x02]).unwrap();
+///
+/// let mut words = [0x10, 0x20];
+/// assert_eq!(spi.transfer(&mut words).unwrap(), [0x11, 0x21]);
+///
+/// let mut response = [0x30];
+/// spi.exec(&mut [Operation::Write(&[0x03]), Operation::Transfer(&mut response)])
+///     .unwrap();
+/// assert_eq!(response, [0x31]);
+///
+/// let loopback = spi.release();
+/// assert_eq!(loopback.written, [0x01, 0x02, 0x10, 0x20, 0x03, 0x30]);
+/// assert_eq!(loopback.received, None);
+/// ```
+pub struct FullDuplexSpi<S> {
+    spi: S,
+}
+
+impl<S> FullDuplexSpi<S> {
+    /// Creates a new `FullDuplexSpi` wrapping a `FullDuplex` implementation
+    pub fn new(spi: S) -> Self {
+        Self { spi }
+    }
+
+    /// Destroys the adapter and returns the wrapped `FullDuplex` implementation
+    pub fn release(self) -> S {
+        self.spi
+    }
+}
+
+impl<S: ErrorType> ErrorType for FullDuplexSpi<S> {
+    type Error = S::Error;
+}
+
+impl<W, S> Transfer<W> for FullDuplexSpi<S>
+where
+    W: Copy,
+    S: FullDuplex<W>,
+{
+    fn transfer<'w>(&mut self, words: &'w mut [W]) -> Result<&'w [W], Self::Error> {
+        for word in words.iter_mut() {
+            nb::block!(self.spi.write(*word))?;
+            *word = nb::block!(self.spi.read())?;
+        }
+
+        Ok(words)
+    }
+}
+
+impl<W, S> Write<W> for FullDuplexSpi<S>
+where
+    W: Copy,
+    S: FullDuplex<W>,
+{
+    fn write(&mut self, words: &[W]) -> Result<(), Self::Error> {
+        for word in words {
+            nb::block!(self.spi.write(*word))?;
+            nb::block!(self.spi.read())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W, S> WriteIter<W> for FullDuplexSpi<S>
+where
+    W: Copy,
+    S: FullDuplex<W>,
+{
+    fn write_iter<WI>(&mut self, words: WI) -> Result<(), Self::Error>
+    where
+        WI: IntoIterator<Item = W>,
+    {
+        for word in words.into_iter() {
+            nb::block!(self.spi.write(word))?;
+            nb::block!(self.spi.read())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W, S> Transactional<W> for FullDuplexSpi<S>
+where
+    W: Copy + 'static,
+    S: FullDuplex<W>,
+{
+    fn exec<'a>(&mut self, operations: &mut [Operation<'a, W>]) -> Result<(), Self::Error> {
+        for op in operations {
+            match op {
+                Operation::Write(words) => self.write(words)?,
+                Operation::Transfer(words) => self.transfer(words).map(|_| ())?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W, S> SpiBus<W> for FullDuplexSpi<S>
+where
+    W: Copy + 'static,
+    S: FullDuplex<W>,
+{
+    /// All operations are complete when the methods of the other traits return, since every
+    /// word written has been read back.
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
@@ -144,15 +144,26 @@
 //! pub type Serial1 = Serial<USART1>;
 //!
 //! /// Serial interface error
+//! #[derive(Debug)]
 //! pub enum Error {
 //!     /// Buffer overrun
 //!     Overrun,
 //!     // omitted: other error variants
 //! }
 //!
-//! impl hal::nb::serial::Read<u8> for Serial<USART1> {
+//! impl hal::nb::serial::Error for Error {
+//!     fn kind(&self) -> hal::nb::serial::ErrorKind {
+//!         match self {
+//!             Error::Overrun => hal::nb::serial::ErrorKind::Overrun,
+//!         }
+//!     }
+//! }
+//!
+//! impl hal::nb::serial::ErrorType for Serial<USART1> {
 //!     type Error = Error;
+//! }
 //!
+//! impl hal::nb::serial::Read<u8> for Serial<USART1> {
 //!     fn read(&mut self) -> nb::Result<u8, Error> {
 //!         // read the status register
 //!         let isr = self.usart.isr.read();
@@ -173,8 +184,6 @@
 //! }
 //!
 //! impl hal::nb::serial::Write<u8> for Serial<USART1> {
-//!     type Error = Error;
-//!
 //!     fn write(&mut self, byte: u8) -> nb::Result<(), Error> {
 //!         // Similar to the `read` implementation
 //!         # Ok(())
@@ -273,12 +282,16 @@
 //!
 //! use hal::nb::serial::Write;
 //! use hal::nb::timer::CountDown;
+//! # use std as core;
+//! use ::core::convert::Infallible;
 //!
 //! enum Error<SE, TE> {
 //!     /// Serial interface error
 //!     Serial(SE),
+//!     /// Timer error
+//!     Timer(TE),
 //!     /// Timeout error
-//!     TimedOut(TE),
+//!     TimedOut,
 //! }
 //!
 //! fn read_with_timeout<S, T>(
@@ -287,10 +300,10 @@
 //!     timeout: T::Time,
 //! ) -> Result<u8, Error<S::Error, T::Error>>
 //! where
-//!     T: hal::nb::timer::CountDown<Error = ()>,
+//!     T: hal::nb::timer::CountDown<Error = Infallible>,
 //!     S: hal::nb::serial::Read<u8>,
 //! {
-//!     timer.start(timeout).map_err(Error::TimedOut)?;
+//!     timer.start(timeout).map_err(Error::Timer)?;
 //!
 //!     loop {
 //!         match serial.read() {
@@ -311,7 +324,7 @@
 //!             },
 //!             // no timeout yet, try again
 //!             Err(nb::Error::WouldBlock) => continue,
-//!             Ok(()) => return Err(Error::TimedOut(())),
+//!             Ok(()) => return Err(Error::TimedOut),
 //!         }
 //!     }
 //! }
@@ -395,8 +408,10 @@
 //! #     fn deref_mut(&mut self) -> &mut T { self.0 }
 //! # }
 //! # struct Serial1;
-//! # impl hal::nb::serial::Write<u8> for Serial1 {
+//! # impl hal::nb::serial::ErrorType for Serial1 {
 //! #   type Error = Infallible;
+//! # }
+//! # impl hal::nb::serial::Write<u8> for Serial1 {
 //! #   fn write(&mut self, _: u8) -> nb::Result<(), Infallible> { Err(::nb::Error::WouldBlock) }
 //! #   fn flush(&mut self) -> nb::Result<(), Infallible> { Err(::nb::Error::WouldBlock) }
 //! # }
@@ -414,6 +429,9 @@
 #![deny(missing_docs)]
 #![no_std]
 
+#[cfg(feature = "async")]
+pub mod asynch;
+pub mod bitbang;
 pub mod blocking;
 pub mod fmt;
 pub mod nb;
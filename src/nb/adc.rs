@@ -1,5 +1,64 @@
 //! Analog-digital conversion traits
 
+/// ADC error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic ADC error kind
+    ///
+    /// By using this method, ADC errors freely defined by HAL implementations
+    /// can be converted to a set of generic ADC errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// ADC error kind
+///
+/// This represents a common set of ADC operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common ADC errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The previous value was overwritten because it was not read in a timely manner
+    Overcapture,
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overcapture => write!(
+                f,
+                "The previous value was overwritten because it was not read in a timely manner"
+            ),
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+/// ADC error type trait
+///
+/// This just defines the error type, to be used by the other ADC traits.
+pub trait ErrorType {
+    /// Error type
+    type Error: Error;
+}
+
 /// A marker trait to identify MCU pins that can be used as inputs to an ADC channel.
 ///
 /// This marker trait denotes an object, i.e. a GPIO pin, that is ready for use as an input to the
@@ -55,7 +114,7 @@ pub trait Channel<ADC> {
 /// of the request (in contrast to continuous asynchronous sampling).
 ///
 /// ```
-/// use embedded_hal::nb::adc::{Channel, OneShot};
+/// use embedded_hal::nb::adc::{Channel, ErrorKind, ErrorType, OneShot};
 ///
 /// struct MyAdc; // 10-bit ADC, with 5 channels
 /// # impl MyAdc {
@@ -64,13 +123,15 @@ pub trait Channel<ADC> {
 /// #     pub fn do_conversion(&mut self, chan: u8) -> u16 { 0xAA55_u16 }
 /// # }
 ///
+/// impl ErrorType for MyAdc {
+///    type Error = ErrorKind;
+/// }
+///
 /// impl<WORD, PIN> OneShot<MyAdc, WORD, PIN> for MyAdc
 /// where
 ///    WORD: From<u16>,
 ///    PIN: Channel<MyAdc, ID=u8>,
 /// {
-///    type Error = ();
-///
 ///    fn read(&mut self, pin: &mut PIN) -> nb::Result<WORD, Self::Error> {
 ///        let chan = 1 << pin.channel();
 ///        self.power_up();
@@ -80,10 +141,7 @@ pub trait Channel<ADC> {
 ///    }
 /// }
 /// ```
-pub trait OneShot<ADC, Word, Pin: Channel<ADC>> {
-    /// Error type returned by ADC methods
-    type Error;
-
+pub trait OneShot<ADC, Word, Pin: Channel<ADC>>: ErrorType {
     /// Request that the ADC begin a conversion on the specified pin
     ///
     /// This method takes a `Pin` reference, as it is expected that the ADC will be able to sample
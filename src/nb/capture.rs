@@ -1,5 +1,56 @@
 //! Input capture
 
+/// Capture error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic capture error kind
+    ///
+    /// By using this method, capture errors freely defined by HAL implementations
+    /// can be converted to a set of generic capture errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// Capture error kind
+///
+/// This represents a common set of capture operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common capture errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The previous value was overwritten because it was not read in a timely manner
+    Overcapture,
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Overcapture => write!(
+                f,
+                "The previous value was overwritten because it was not read in a timely manner"
+            ),
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
 /// Input capture
 ///
 /// # Examples
@@ -57,7 +108,7 @@ pub trait Capture {
     ///
     /// - *overcapture*, the previous capture value was overwritten because it
     ///   was not read in a timely manner
-    type Error;
+    type Error: Error;
 
     /// Enumeration of channels that can be used with this `Capture` interface
     ///
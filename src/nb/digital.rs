@@ -0,0 +1,52 @@
+//! Digital I/O
+
+pub use crate::blocking::digital::{Error, ErrorKind, ErrorType};
+use crate::blocking::digital::{InputPin, PollingWait};
+
+/// Wait for the state of an input pin
+///
+/// Each method returns `WouldBlock` until the awaited level or edge is detected. Edge detection
+/// starts with the first call of an edge-waiting method and ends when it returns `Ok(())`: the
+/// method must be called again until then, and only edges happening in between are detected.
+pub trait Wait: ErrorType {
+    /// Checks that the pin is high
+    fn wait_for_high(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Checks that the pin is low
+    fn wait_for_low(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Checks that the pin underwent a transition from low to high
+    fn wait_for_rising_edge(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Checks that the pin underwent a transition from high to low
+    fn wait_for_falling_edge(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Checks that the pin underwent any transition, i.e low to high OR high to low
+    fn wait_for_any_edge(&mut self) -> nb::Result<(), Self::Error>;
+}
+
+/// Samples the pin once per call
+///
+/// An edge-waiting method samples the pin once when it is first called, then compares each new
+/// sample with the previous one.
+impl<P: InputPin> Wait for PollingWait<P> {
+    fn wait_for_high(&mut self) -> nb::Result<(), Self::Error> {
+        self.poll_level(true)
+    }
+
+    fn wait_for_low(&mut self) -> nb::Result<(), Self::Error> {
+        self.poll_level(false)
+    }
+
+    fn wait_for_rising_edge(&mut self) -> nb::Result<(), Self::Error> {
+        self.poll_edge(true, false)
+    }
+
+    fn wait_for_falling_edge(&mut self) -> nb::Result<(), Self::Error> {
+        self.poll_edge(false, true)
+    }
+
+    fn wait_for_any_edge(&mut self) -> nb::Result<(), Self::Error> {
+        self.poll_edge(true, true)
+    }
+}
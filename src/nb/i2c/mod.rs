@@ -0,0 +1,10 @@
+//! Inter-Integrated Circuit
+//!
+//! Only the target side of I2C has a non-blocking API, see the [`target`](target/index.html)
+//! module.
+
+pub use crate::blocking::i2c::{
+    AddressMode, Error, ErrorKind, ErrorType, NoAcknowledgeSource, SevenBitAddress, TenBitAddress,
+};
+
+pub mod target;
@@ -0,0 +1,43 @@
+//! I2C target
+
+pub use crate::blocking::i2c::target::Request;
+use crate::blocking::i2c::{AddressMode, ErrorType, SevenBitAddress};
+
+/// I2C target
+///
+/// The target answers the controller byte by byte: each method returns `WouldBlock` until the
+/// controller reaches the corresponding step of the transaction. The target holds the bus
+/// (stretching the clock) while the controller waits for it, e.g. while no byte to respond
+/// with has been given to [`write`](#tymethod.write).
+///
+/// See the [blocking target trait](../../../blocking/i2c/target/trait.Target.html) for a
+/// description of the requests.
+pub trait Target<A: AddressMode = SevenBitAddress>: ErrorType {
+    /// Sets the addresses the target answers, replacing the previous ones
+    ///
+    /// Implementations supporting fewer addresses than requested should return an error.
+    fn listen(&mut self, addresses: &[A]) -> Result<(), Self::Error>;
+
+    /// Sets whether the target answers general calls
+    fn set_general_call(&mut self, enabled: bool) -> Result<(), Self::Error>;
+
+    /// Returns the request of the controller once it has addressed the target
+    ///
+    /// The address has been acknowledged when this method returns `Ok`.
+    fn request(&mut self) -> nb::Result<Request<A>, Self::Error>;
+
+    /// Acknowledges and returns the next byte written by the controller
+    ///
+    /// Returns `None` once the controller has ended the write with a repeated start or stop
+    /// condition.
+    fn read(&mut self) -> nb::Result<Option<u8>, Self::Error>;
+
+    /// Sends `byte` to the controller reading from the target
+    ///
+    /// Returns whether the controller acknowledged the byte, i.e. whether it will read another
+    /// one.
+    fn write(&mut self, byte: u8) -> nb::Result<bool, Self::Error>;
+
+    /// Does not acknowledge the next byte written by the controller
+    fn nack(&mut self) -> nb::Result<(), Self::Error>;
+}
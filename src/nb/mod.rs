@@ -17,6 +17,8 @@
 pub use nb::{block, Error, Result};
 pub mod adc;
 pub mod capture;
+pub mod digital;
+pub mod i2c;
 pub mod serial;
 pub mod spi;
 pub mod timer;
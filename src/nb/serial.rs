@@ -1,22 +1,18 @@
 //! Serial interface
 
+pub use crate::blocking::serial::{Error, ErrorKind, ErrorType};
+
 /// Read half of a serial interface
 ///
 /// Some serial interfaces support different data sizes (8 bits, 9 bits, etc.);
 /// This can be encoded in this trait via the `Word` type parameter.
-pub trait Read<Word> {
-    /// Read error
-    type Error;
-
+pub trait Read<Word>: ErrorType {
     /// Reads a single word from the serial interface
     fn read(&mut self) -> nb::Result<Word, Self::Error>;
 }
 
 /// Write half of a serial interface
-pub trait Write<Word> {
-    /// Write error
-    type Error;
-
+pub trait Write<Word>: ErrorType {
     /// Writes a single word to the serial interface
     fn write(&mut self, word: Word) -> nb::Result<(), Self::Error>;
 
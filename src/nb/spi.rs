@@ -1,5 +1,7 @@
 //! Serial Peripheral Interface
 
+pub use crate::blocking::spi::{Error, ErrorKind, ErrorType};
+
 /// Full duplex (master mode)
 ///
 /// # Notes
@@ -16,10 +18,7 @@
 ///
 /// - Some SPIs can work with 8-bit *and* 16-bit words. You can overload this trait with different
 /// `Word` types to allow operation in both modes.
-pub trait FullDuplex<Word> {
-    /// An enumeration of SPI errors
-    type Error;
-
+pub trait FullDuplex<Word>: ErrorType {
     /// Reads the word stored in the shift register
     ///
     /// **NOTE** A word must be sent to the slave before attempting to call this
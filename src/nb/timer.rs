@@ -1,5 +1,58 @@
 //! Timers
 
+/// Timer error
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic timer error kind
+    ///
+    /// By using this method, timer errors freely defined by HAL implementations
+    /// can be converted to a set of generic timer errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl Error for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+/// Timer error kind
+///
+/// This represents a common set of timer operation errors. HAL implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common timer errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A different error occurred. The original error may contain more information.
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Other => write!(
+                f,
+                "A different error occurred. The original error may contain more information"
+            ),
+        }
+    }
+}
+
+/// Timer error type trait
+///
+/// This just defines the error type, to be used by the other timer traits.
+pub trait ErrorType {
+    /// Error type
+    type Error: Error;
+}
+
 /// A count down timer
 ///
 /// # Contract
@@ -47,19 +100,16 @@
 /// #     pub fn on(&mut self) {}
 /// # }
 /// # struct Timer6;
-/// # impl hal::nb::timer::CountDown for Timer6 {
+/// # impl hal::nb::timer::ErrorType for Timer6 {
 /// #     type Error = Infallible;
+/// # }
+/// # impl hal::nb::timer::CountDown for Timer6 {
 /// #     type Time = Seconds;
 /// #     fn start<T>(&mut self, _: T) -> Result<(), Self::Error> where T: Into<Seconds> { Ok(()) }
 /// #     fn wait(&mut self) -> ::nb::Result<(), Infallible> { Ok(()) }
 /// # }
 /// ```
-pub trait CountDown {
-    /// An enumeration of `CountDown` errors.
-    ///
-    /// For infallible implementations, will be `Infallible`
-    type Error;
-
+pub trait CountDown: ErrorType {
     /// The unit of time used by this timer
     type Time;
 